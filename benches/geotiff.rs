@@ -0,0 +1,42 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use las::crs::{GeoTiffCrs, GeoTiffData, GeoTiffKeyEntry};
+use las_crs::get_epsg_from_geotiff_crs;
+use std::hint::black_box;
+
+/// Build a GeoTiff CRS with `n_keys` entries, where the EPSG keys are the last entries
+fn geotiff_crs(n_keys: u16) -> GeoTiffCrs {
+    let mut entries = vec![GeoTiffKeyEntry {
+        id: 1024,
+        data: GeoTiffData::U16(1),
+    }];
+    // filler keys that are skipped by the parser, f.ex units and descriptions
+    for i in 0..n_keys.saturating_sub(3) {
+        entries.push(GeoTiffKeyEntry {
+            id: 5000 + i,
+            data: GeoTiffData::U16(9001),
+        });
+    }
+    entries.push(GeoTiffKeyEntry {
+        id: 3072,
+        data: GeoTiffData::U16(25832),
+    });
+    entries.push(GeoTiffKeyEntry {
+        id: 4096,
+        data: GeoTiffData::U16(5941),
+    });
+    GeoTiffCrs { entries }
+}
+
+fn bench_geotiff(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get_epsg_from_geotiff_crs");
+    for n_keys in [5, 10, 20, 50] {
+        let crs = geotiff_crs(n_keys);
+        group.bench_with_input(BenchmarkId::from_parameter(n_keys), &crs, |b, crs| {
+            b.iter(|| get_epsg_from_geotiff_crs(black_box(crs)).is_ok())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_geotiff);
+criterion_main!(benches);
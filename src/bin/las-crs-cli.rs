@@ -0,0 +1,232 @@
+//! Print the EPSG code(s) of lidar files
+//!
+//! ```text
+//! las-crs-cli [--format bare|epsg|json|csv] <FILE>...
+//! las-crs-cli [--format bare|epsg|json|csv] --batch <DIR>
+//! ```
+//!
+//! In batch mode every .las and .laz file in the directory is parsed,
+//! followed by a summary report on stderr.
+//! The exit code is 1 if any file has no CRS or could not be parsed.
+
+use las_crs::{EpsgCRS, get_epsg_crs_from_path};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: las-crs-cli [--format bare|epsg|json|csv] (<FILE>... | --batch <DIR>)";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// `25832` or `25832+5941`
+    Bare,
+    /// `EPSG:25832` or `EPSG:25832+5941`
+    Epsg,
+    /// One JSON object per line
+    Json,
+    /// `path,horizontal,vertical` with a header row
+    Csv,
+}
+
+impl Format {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "bare" => Some(Format::Bare),
+            "epsg" => Some(Format::Epsg),
+            "json" => Some(Format::Json),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        }
+    }
+
+    /// The output line for the CRS of the file
+    fn format_crs(self, file: &Path, crs: &EpsgCRS, with_path: bool) -> String {
+        let path = file.display();
+        let code = match crs.get_vertical() {
+            Some(vc) => format!("{}+{vc}", crs.get_horizontal()),
+            None => crs.get_horizontal().to_string(),
+        };
+        match self {
+            Format::Bare if with_path => format!("{path}\t{code}"),
+            Format::Bare => code,
+            Format::Epsg if with_path => format!("{path}\tEPSG:{code}"),
+            Format::Epsg => format!("EPSG:{code}"),
+            Format::Json => crs.to_json_with_path(file),
+            Format::Csv => format!(
+                "{},{},{}",
+                csv_field(&file.to_string_lossy()),
+                crs.get_horizontal(),
+                crs.get_vertical()
+                    .map(|vc| vc.to_string())
+                    .unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// Quote the field if it contains a comma, quote or line break, with quotes doubled, as in RFC 4180
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Files that had a CRS, had none and could not be parsed
+#[derive(Default)]
+struct Summary {
+    parsed: usize,
+    no_crs: usize,
+    failed: usize,
+}
+
+fn main() -> ExitCode {
+    let mut format = Format::Bare;
+    let mut batch_dir = None;
+    let mut files = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => match args.next().as_deref().and_then(Format::parse) {
+                Some(f) => format = f,
+                None => return usage_error(),
+            },
+            "--batch" => match args.next() {
+                Some(dir) => batch_dir = Some(PathBuf::from(dir)),
+                None => return usage_error(),
+            },
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ => files.push(PathBuf::from(arg)),
+        }
+    }
+
+    if let Some(dir) = &batch_dir {
+        match lidar_files_in(dir) {
+            Ok(found) => files.extend(found),
+            Err(e) => {
+                eprintln!("Unable to read the directory {}: {e}", dir.display());
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    if files.is_empty() && batch_dir.is_none() {
+        return usage_error();
+    }
+
+    if format == Format::Csv {
+        println!("path,horizontal,vertical");
+    }
+    let with_path = files.len() > 1 || batch_dir.is_some();
+    let mut summary = Summary::default();
+    for path in &files {
+        match get_epsg_crs_from_path(path) {
+            Ok(Some(crs)) => {
+                summary.parsed += 1;
+                println!("{}", format.format_crs(path, &crs, with_path));
+            }
+            Ok(None) => {
+                summary.no_crs += 1;
+                eprintln!("{}: no CRS", path.display());
+            }
+            Err(e) => {
+                summary.failed += 1;
+                eprintln!("{}: {e}", path.display());
+            }
+        }
+    }
+
+    if batch_dir.is_some() {
+        eprintln!(
+            "{} files: {} parsed, {} without CRS, {} failed",
+            files.len(),
+            summary.parsed,
+            summary.no_crs,
+            summary.failed
+        );
+    }
+
+    if summary.no_crs + summary.failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn usage_error() -> ExitCode {
+    eprintln!("{USAGE}");
+    ExitCode::from(2)
+}
+
+/// The .las and .laz files in the directory, sorted by path
+fn lidar_files_in(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_lidar = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("las") || ext.eq_ignore_ascii_case("laz"));
+        if is_lidar && path.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_crs() {
+        let crs = EpsgCRS::new(25832, Some(5941)).unwrap();
+        let file = Path::new("tile.laz");
+
+        assert_eq!(Format::Bare.format_crs(file, &crs, false), "25832+5941");
+        assert_eq!(
+            Format::Bare.format_crs(file, &crs, true),
+            "tile.laz\t25832+5941"
+        );
+        assert_eq!(
+            Format::Epsg.format_crs(file, &crs, false),
+            "EPSG:25832+5941"
+        );
+        assert_eq!(
+            Format::Epsg.format_crs(file, &crs, true),
+            "tile.laz\tEPSG:25832+5941"
+        );
+        assert_eq!(
+            Format::Json.format_crs(file, &crs, false),
+            r#"{"path":"tile.laz","crs":{"horizontal":25832,"vertical":5941}}"#
+        );
+        assert_eq!(
+            Format::Csv.format_crs(file, &crs, false),
+            "tile.laz,25832,5941"
+        );
+
+        let crs = EpsgCRS::new(2994, None).unwrap();
+        assert_eq!(Format::Csv.format_crs(file, &crs, false), "tile.laz,2994,");
+    }
+
+    #[test]
+    fn test_format_crs_csv_escaping() {
+        let crs = EpsgCRS::new(2994, None).unwrap();
+        assert_eq!(
+            Format::Csv.format_crs(Path::new("a,b.las"), &crs, true),
+            r#""a,b.las",2994,"#
+        );
+        assert_eq!(
+            Format::Csv.format_crs(Path::new(r#"say "hi".las"#), &crs, true),
+            r#""say ""hi"".las",2994,"#
+        );
+        assert_eq!(
+            Format::Csv.format_crs(Path::new("a\nb.las"), &crs, true),
+            "\"a\nb.las\",2994,"
+        );
+    }
+}
@@ -4,6 +4,8 @@
 //! of [las::Header::get_geotiff_crs] or [las::Header::get_wkt_crs_bytes] respectively
 //!
 //! The library should be able to parse CRS's stored in WKT-CRS v1 and v2 and GeoTiff U16 (E)VLR(s) stored in both las and laz files (with the laz feature flag activated).
+//! The laz feature enables the laz feature of the las crate, which is re-exported as `las_crs::las`
+//! so that readers can be created without enabling the feature of the las dependency separately.
 //!
 //! The CRS is returend in a `Result<EpsgCRS, crate::Error>`
 //! CRS has the fields horizontal, which is a u16 EPSG code, and vertical, which is an optional u16 EPSG code.
@@ -17,32 +19,122 @@
 //! This is because the las 1.4 spec (which .copc.laz demands), requires a WKT-CRS (E)VLR to be present.
 //! These VLRs often contain the invalid EPSG code 0 and trying to extract that code will return a BadHorizontalCodeParsed Error.
 //!
-//! Parsing EPSG codes from user-defined CRS's and CRS's stored in GeoTiff String or Double data is not supported.
+//! Parsing EPSG codes from user-defined CRS's and CRS's stored in GeoTiff String or Double data is not supported,
+//! except for `EPSG:<code>` tokens found in the GeoTiff citation keys.
 //! But the relevant [las::crs::GeoTiffData] is returned with the `Error::UnimplementedForGeoTiffStringAndDoubleData(las::crs::GeoTiffData)`
 //! If you have a Lidar file with CRS defined in this way please make an issue on Github so I can create tests for it
 //! I have yet to see a Lidar file with CRS defined in that way
 
 use las::{
-    Header,
-    crs::{GeoTiffCrs, GeoTiffData},
+    Bounds, Builder, Header, Vlr,
+    crs::{GeoTiffCrs, GeoTiffData, GeoTiffKeyEntry},
+};
+use log::{Level, log, log_enabled};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    io::{Read, Seek, SeekFrom},
+    ops::{Range, RangeInclusive},
+    path::PathBuf,
+    sync::OnceLock,
 };
-use log::{Level, log};
 use thiserror::Error;
 
+/// The las crate, with its laz feature enabled through the laz feature of this crate
+#[cfg(feature = "laz")]
+pub use las;
+
 type Result<T> = std::result::Result<T, Error>;
 
-pub const EPSG_RANGE: std::ops::RangeInclusive<u16> = 1024..=(i16::MAX as u16);
+/// The lower bound of [EPSG_RANGE]
+pub const EPSG_RANGE_MIN: u16 = 1024;
+
+/// The upper bound of [EPSG_RANGE]
+pub const EPSG_RANGE_MAX: u16 = i16::MAX as u16;
+
+pub const EPSG_RANGE: RangeInclusive<u16> = EPSG_RANGE_MIN..=EPSG_RANGE_MAX;
+
+/// The WKT-CRS dialects [get_epsg_from_wkt_crs_bytes] handles
+pub const SUPPORTED_WKT: &[&str] = &["WKT1", "WKT2-2015", "WKT2-2019"];
+
+/// Metadata about a CRS format the library can parse
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrsFormatInfo {
+    /// Name of the format
+    pub format: &'static str,
+    /// Version or dialect of the format
+    pub version: &'static str,
+    /// What is parsed from the format
+    pub notes: &'static str,
+}
+
+/// List the CRS formats the library can parse EPSG codes from
+pub fn list_supported_formats() -> &'static [CrsFormatInfo] {
+    const FORMATS: &[CrsFormatInfo] = &[
+        CrsFormatInfo {
+            format: "WKT-CRS",
+            version: "WKT1",
+            notes: "Horizontal code and optional VERT_CS code",
+        },
+        CrsFormatInfo {
+            format: "WKT-CRS",
+            version: "WKT2-2015",
+            notes: "Horizontal code and optional VERTCRS code",
+        },
+        CrsFormatInfo {
+            format: "WKT-CRS",
+            version: "WKT2-2019",
+            notes: "Horizontal code and optional VERTCRS code",
+        },
+        CrsFormatInfo {
+            format: "GeoTiff",
+            version: "1.0",
+            notes: "U16 data in keys 2048, 3072 and 4096, or EPSG:<code> tokens in the citation keys",
+        },
+    ];
+    FORMATS
+}
+
+/// The GeoTiff keys that influence the codes parsed by [get_epsg_from_geotiff_crs]
+///
+/// 1024 (model type), 2048 (geographic type), 3072 (projected type), 4096 (vertical type)
+/// and the citation keys 1026, 2049 and 3073 which are scanned for `EPSG:<code>` tokens
+pub const RECOGNIZED_GEOTIFF_KEYS: &[u16] = &[1024, 1026, 2048, 2049, 3072, 3073, 4096];
+
+/// The GeoTiff code for a user-defined CRS
+const USER_DEFINED_CODE: u16 = 32_767;
 
 /// Horizontal and optional vertical CRS given by EPSG code(s)
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg(not(feature = "pub_fields"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct EpsgCRS {
-    /// EPSG code for the horizontal CRS
+    /// EPSG code for the horizontal CRS, in [EPSG_RANGE] (1024-32767)
+    #[cfg_attr(feature = "schemars", schemars(range(min = 1024, max = 32767)))]
     horizontal: u16,
 
-    /// Optional EPSG code for the vertical CRS
+    /// Optional EPSG code for the vertical CRS, in [EPSG_RANGE] (1024-32767) if present
+    #[cfg_attr(feature = "schemars", schemars(range(min = 1024, max = 32767)))]
     vertical: Option<u16>,
 }
 
+/// Horizontal and optional vertical CRS given by EPSG code(s)
+///
+/// The fields are public with the `pub_fields` feature, so writing to them
+/// directly skips the checks against [EPSG_RANGE] done by the checked setters
+#[cfg(feature = "pub_fields")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct EpsgCRS {
+    /// EPSG code for the horizontal CRS, in [EPSG_RANGE] (1024-32767)
+    #[cfg_attr(feature = "schemars", schemars(range(min = 1024, max = 32767)))]
+    pub horizontal: u16,
+
+    /// Optional EPSG code for the vertical CRS, in [EPSG_RANGE] (1024-32767) if present
+    #[cfg_attr(feature = "schemars", schemars(range(min = 1024, max = 32767)))]
+    pub vertical: Option<u16>,
+}
+
 impl EpsgCRS {
     /// Construct a new EpsgCrs both components are checked against EPSG_RANGE
     pub fn new(horizontal_code: u16, vertical_code: Option<u16>) -> Result<Self> {
@@ -85,6 +177,161 @@ impl EpsgCRS {
         self.vertical
     }
 
+    /// get both codes as a `(horizontal, vertical)` tuple, f.ex for destructuring
+    pub fn as_tuple(&self) -> (u16, Option<u16>) {
+        (self.horizontal, self.vertical)
+    }
+
+    /// Parse the EPSG code(s) from the header, same as [ParseEpsgCRS::get_epsg_crs]
+    pub fn from_header(header: &Header) -> Result<Option<EpsgCRS>> {
+        header.get_epsg_crs()
+    }
+
+    /// Parse an EpsgCRS from a string in one of several common formats
+    ///
+    /// Accepts bare integers (`"4326"`), `"EPSG:4326"` in any case, `"EPSG::4326"`,
+    /// OGC URNs (`"urn:ogc:def:crs:EPSG::4326"`) and compound CRS's separated by `+` or `,`
+    /// (`"4326+5773"`, `"EPSG:4326,EPSG:5773"`, `"urn:ogc:def:crs,crs:EPSG::4326,crs:EPSG::5773"`).
+    /// Both components are checked against EPSG_RANGE.
+    ///
+    /// Returns [Error::CodeParseError] if a code is not a `u16`, f.ex `"EPSG:abc"`,
+    /// and [Error::InvalidCrsString] if the string is in none of the formats
+    pub fn from_str_lenient(s: &str) -> Result<Self> {
+        let mut codes = Vec::with_capacity(2);
+        for component in s.split(['+', ',']).map(str::trim) {
+            // the head of a compound OGC URN carries no code
+            if component.eq_ignore_ascii_case("urn:ogc:def:crs") {
+                continue;
+            }
+            let code = match component.rsplit_once(':') {
+                Some((prefix, code)) if prefix.to_ascii_uppercase().contains("EPSG") => code,
+                Some(_) => return Err(Error::InvalidCrsString(s.to_string())),
+                None => component,
+            };
+            codes.push(code.trim().parse::<u16>()?);
+        }
+
+        match codes[..] {
+            [horizontal] => Self::new(horizontal, None),
+            [horizontal, vertical] => Self::new(horizontal, Some(vertical)),
+            _ => Err(Error::InvalidCrsString(s.to_string())),
+        }
+    }
+
+    /// Identify the horizontal CRS from its name, f.ex from the system identifier of a file without CRS (E)VLRs
+    ///
+    /// The name must match one of a small table of common CRS names ([PROJECTION_NAMES]) or be a
+    /// WGS 84, ETRS89 or NAD83 UTM zone (`"WGS 84 / UTM zone 32N"`). Case and whitespace are ignored,
+    /// but there is no fuzzy matching, so a name with anything extra (f.ex a vertical datum) returns `None`
+    pub fn from_projection_name(name: &str) -> Option<EpsgCRS> {
+        let name = normalize_projection_name(name);
+        let horizontal = PROJECTION_NAMES
+            .iter()
+            .find(|(known, _)| normalize_projection_name(known) == name)
+            .map(|(_, code)| *code)
+            .or_else(|| utm_code_from_name(&name))?;
+        Some(EpsgCRS {
+            horizontal,
+            vertical: None,
+        })
+    }
+
+    /// Whether the horizontal CRS is one of the national grids of the country,
+    /// given as an ISO 3166-1 alpha-2 code in any case (f.ex `"NO"`), see [NATIONAL_GRIDS]
+    ///
+    /// Returns `false` for countries not in the table
+    pub fn is_national_grid(&self, country_code: &str) -> bool {
+        NATIONAL_GRIDS
+            .iter()
+            .find(|(country, _)| country.eq_ignore_ascii_case(country_code))
+            .is_some_and(|(_, codes)| codes.contains(&self.horizontal))
+    }
+
+    /// Whether the horizontal or vertical code is a deprecated EPSG code, see [DEPRECATED_CODES]
+    ///
+    /// Only flags the deprecated codes common in lidar data, not every deprecated code of the registry
+    pub fn is_deprecated(&self) -> bool {
+        deprecated_code_successor(self.horizontal).is_some()
+            || self.vertical.and_then(deprecated_code_successor).is_some()
+    }
+
+    /// The CRS with the deprecated code(s) replaced by their successors, see [DEPRECATED_CODES]
+    ///
+    /// Returns `None` if neither code is deprecated
+    pub fn successor(&self) -> Option<EpsgCRS> {
+        if !self.is_deprecated() {
+            return None;
+        }
+        Some(EpsgCRS {
+            horizontal: deprecated_code_successor(self.horizontal).unwrap_or(self.horizontal),
+            vertical: self
+                .vertical
+                .map(|vc| deprecated_code_successor(vc).unwrap_or(vc)),
+        })
+    }
+
+    /// Same as [Self::from_str_lenient], but also returns the [CrsStringFormat] of the string
+    pub fn try_from_str_with_source(s: &str) -> Result<(Self, CrsStringFormat)> {
+        let crs = Self::from_str_lenient(s)?;
+        let upper = s.trim().to_ascii_uppercase();
+        let format = if upper.starts_with("URN:OGC:DEF:CRS") {
+            CrsStringFormat::OgcUrn
+        } else if upper.contains("EPSG::") {
+            CrsStringFormat::EpsgDoubleColon
+        } else if upper.contains("EPSG:") {
+            CrsStringFormat::EpsgPrefix
+        } else {
+            CrsStringFormat::Bare
+        };
+        Ok((crs, format))
+    }
+
+    /// whether the CRS is a compound (3D) CRS, i.e. has a vertical component
+    pub fn is_compound(&self) -> bool {
+        self.vertical.is_some()
+    }
+
+    /// whether the CRS is 2D, i.e. has no vertical component
+    pub fn is_2d(&self) -> bool {
+        !self.is_compound()
+    }
+
+    /// whether the CRS only has a horizontal component, alias of [Self::is_2d]
+    pub fn is_horizontal_only(&self) -> bool {
+        self.is_2d()
+    }
+
+    /// The components shared by both CRS's, f.ex for checking that tiles can be merged
+    ///
+    /// Returns `None` if the horizontal codes differ, otherwise the horizontal code
+    /// and the vertical code if it is the same in both
+    pub fn intersection(&self, other: &EpsgCRS) -> Option<EpsgCRS> {
+        if self.horizontal != other.horizontal {
+            return None;
+        }
+        Some(EpsgCRS {
+            horizontal: self.horizontal,
+            vertical: self.vertical.filter(|_| self.vertical == other.vertical),
+        })
+    }
+
+    /// Categorize the vertical CRS by its EPSG code range, without a registry lookup
+    ///
+    /// The categorization is a heuristic: the global geoid models (EGM84, EGM96 and EGM2008) are [VerticalCategory::Geoid],
+    /// the 3D geographic CRS's of WGS 84 and ETRS89 (EPSG:4979 and 4937) are [VerticalCategory::EllipsoidalHeight],
+    /// 5700-5799 are [VerticalCategory::MeanSeaLevel] and 5800-5899 [VerticalCategory::LocalDatum].
+    /// Returns `None` if the CRS has no vertical component
+    pub fn vertical_category(&self) -> Option<VerticalCategory> {
+        let category = match self.vertical? {
+            3855 | 5773 | 5798 => VerticalCategory::Geoid,
+            4937 | 4979 => VerticalCategory::EllipsoidalHeight,
+            5700..=5799 => VerticalCategory::MeanSeaLevel,
+            5800..=5899 => VerticalCategory::LocalDatum,
+            _ => VerticalCategory::Unknown,
+        };
+        Some(category)
+    }
+
     /// set the horizontal code, the new code is checked against EPSG_RANGE before setting
     pub fn set_horizontal(&mut self, horizontal_code: u16) -> Result<()> {
         if EPSG_RANGE.contains(&horizontal_code) {
@@ -105,6 +352,31 @@ impl EpsgCRS {
         }
     }
 
+    /// replace the existing vertical code, the new code is checked against EPSG_RANGE before setting.
+    /// Unlike [Self::set_vertical], returns [Error::NoVerticalToReplace] if there is no vertical code,
+    /// so a vertical component is never added by accident
+    pub fn replace_vertical(&mut self, vertical_code: u16) -> Result<()> {
+        if self.vertical.is_none() {
+            return Err(Error::NoVerticalToReplace);
+        }
+        self.set_vertical(vertical_code)
+    }
+
+    /// remove the vertical code
+    pub fn clear_vertical(&mut self) {
+        self.vertical = None;
+    }
+
+    /// return a copy with the vertical code set if `Some`, the code is checked against EPSG_RANGE,
+    /// or cleared if `None`
+    pub fn with_vertical_option(mut self, vertical_code: Option<u16>) -> Result<Self> {
+        match vertical_code {
+            Some(vc) => self.set_vertical(vc)?,
+            None => self.clear_vertical(),
+        }
+        Ok(self)
+    }
+
     /// set the horizontal code without checking against EPSG_RANGE
     pub fn set_horizontal_unchecked(&mut self, horizontal_code: u16) {
         self.horizontal = horizontal_code;
@@ -114,267 +386,4519 @@ impl EpsgCRS {
     pub fn set_vertical_unchecked(&mut self, vertical_code: u16) {
         self.vertical = Some(vertical_code)
     }
-}
-
-/// Error enum
-#[derive(Error, Debug)]
-pub enum Error {
-    /// Error propagated from the Las lib
-    #[error(transparent)]
-    LasError(#[from] las::Error),
-    /// User defined CRS cannot be parsed
-    #[error("Parsing of User Defined CRS not implemented")]
-    UserDefinedCrs,
-    /// Could not parse the CRS from the WKT-data
-    #[error("Unable to parse the found WKT-CRS (E)VLR")]
-    UnreadableWktCrs,
-    /// The parsed horizontal code is outside of [EPSG_RANGE]
-    #[error("The parsed code for the horizontal component is outside of the EPSG-range")]
-    BadHorizontalCodeParsed(EpsgCRS),
-    /// Cannot parse EPSG code from ascii string or double data
-    #[error("The CRS parser does not handle CRS's defined by Geotiff String and Double data")]
-    UnimplementedForGeoTiffStringAndDoubleData(GeoTiffData),
-    /// The provided code for setting is outside of EPSG_RANGE
-    #[error("The provided code for setting is outside of EPSG_RANGE")]
-    SetBadCode(u16),
-    /// The EPSG CRS is outside of EPSG_RANGE
-    #[error("A component of the EPSG code is outside of EPSG_RANGE")]
-    BadEPSGCrs,
-}
 
-pub trait ParseEpsgCRS {
-    fn get_epsg_crs(&self) -> Result<Option<EpsgCRS>>;
-}
+    /// A stable, filesystem-safe identifier for the CRS, f.ex `"h25832_v5941"` or `"h25832"`
+    ///
+    /// Meant for use as a cache key or directory name
+    pub fn cache_key(&self) -> String {
+        match self.vertical {
+            Some(vc) => format!("h{}_v{}", self.horizontal, vc),
+            None => format!("h{}", self.horizontal),
+        }
+    }
 
-impl ParseEpsgCRS for Header {
-    /// Parse the EPSG coordinate reference system (CRSes) code(s) from the header.
+    /// Encode as the payload of a GeoTiff key directory (E)VLR (`LASF_Projection`, record id 34735)
     ///
-    /// Las stores CRS-info in (E)VLRs either as Well Known Text (WKT) or in GeoTIff-format
-    /// **Most**, but not all, CRS' used for Aerial Lidar has an associated EPSG code.
-    /// Use this function to try and parse the EPSG code(s) from the header.
+    /// The payload is little-endian u16s, a header of key directory version, key revision,
+    /// minor revision and number of keys followed by the key entries of id, tag location (0, inline),
+    /// count (1) and value. Horizontal codes in 4000-4999 (geographic 2D CRS's) are written with
+    /// the geographic model type in key 2048, other codes with the projected model type in key 3072.
+    /// The vertical code is written in key 4096
+    pub fn encode_as_geotiff_vlr_bytes(&self) -> Vec<u8> {
+        let mut keys = Vec::with_capacity(3);
+        if (4000..5000).contains(&self.horizontal) {
+            keys.push([1024, 0, 1, 2]);
+            keys.push([2048, 0, 1, self.horizontal]);
+        } else {
+            keys.push([1024, 0, 1, 1]);
+            keys.push([3072, 0, 1, self.horizontal]);
+        }
+        if let Some(vc) = self.vertical {
+            keys.push([4096, 0, 1, vc]);
+        }
+
+        let header = [1, 1, 0, keys.len() as u16];
+        header
+            .into_iter()
+            .chain(keys.into_iter().flatten())
+            .flat_map(u16::to_le_bytes)
+            .collect()
+    }
+
+    /// Encode as the UTF-8 payload of a WKT-CRS (E)VLR (`LASF_Projection`, record id 2112)
     ///
-    /// WKT takes precedence over GeoTiff in this function, but they should not co-exist.
+    /// The WKT2 is minimal, the CRS's are only identified by their `ID` nodes, f.ex
+    /// `COMPOUNDCRS["EPSG:25832 + EPSG:5941",PROJCRS["EPSG:25832",ID["EPSG",25832]],VERTCRS["EPSG:5941",ID["EPSG",5941]]]`.
+    /// Horizontal codes in 4000-4999 (geographic 2D CRS's) are written as `GEOGCRS`, other codes as `PROJCRS`
+    pub fn encode_as_wkt2_vlr_bytes(&self) -> Vec<u8> {
+        let keyword = if (4000..5000).contains(&self.horizontal) {
+            "GEOGCRS"
+        } else {
+            "PROJCRS"
+        };
+        let horizontal = format!(r#"{keyword}["EPSG:{0}",ID["EPSG",{0}]]"#, self.horizontal);
+        let wkt = match self.vertical {
+            Some(vc) => format!(
+                r#"COMPOUNDCRS["EPSG:{} + EPSG:{vc}",{horizontal},VERTCRS["EPSG:{vc}",ID["EPSG",{vc}]]]"#,
+                self.horizontal
+            ),
+            None => horizontal,
+        };
+        wkt.into_bytes()
+    }
+
+    /// Serialize to a minimal WKT1 string, for tools that do not accept the WKT2 of [Self::encode_as_wkt2_vlr_bytes]
     ///
-    /// Just because this function fails does not mean that no CRS-data is available.
-    /// Use functions [Self::get_wkt_crs_bytes] or [Self::get_geotiff_crs] to get all data stored in the CRS-(E)VLRs.
+    /// Like the WKT2 the CRS's are only identified by their authority, but with the WKT1 keywords
+    /// (`COMPD_CS`, `PROJCS`, `GEOGCS` and `VERT_CS`) and `AUTHORITY` nodes with the code quoted, f.ex
+    /// `COMPD_CS["EPSG:25832 + EPSG:5941",PROJCS["EPSG:25832",AUTHORITY["EPSG","25832"]],VERT_CS["EPSG:5941",AUTHORITY["EPSG","5941"]]]`.
+    /// Horizontal codes in 4000-4999 (geographic 2D CRS's) are written as `GEOGCS`, other codes as `PROJCS`.
+    /// Consumers must look the definitions up by the authority codes, as the datum, projection and units are left out
+    pub fn to_wkt1(&self) -> String {
+        let keyword = if (4000..5000).contains(&self.horizontal) {
+            "GEOGCS"
+        } else {
+            "PROJCS"
+        };
+        let horizontal = format!(
+            r#"{keyword}["EPSG:{0}",AUTHORITY["EPSG","{0}"]]"#,
+            self.horizontal
+        );
+        match self.vertical {
+            Some(vc) => format!(
+                r#"COMPD_CS["EPSG:{} + EPSG:{vc}",{horizontal},VERT_CS["EPSG:{vc}",AUTHORITY["EPSG","{vc}"]]]"#,
+                self.horizontal
+            ),
+            None => horizontal,
+        }
+    }
+
+    /// Serialize to a JSON object, f.ex `{"horizontal":25832,"vertical":5941}`
     ///
-    /// Parsing code(s) from WKT-CRS v1 or v2 and GeoTiff U16-data is supported.
+    /// A missing vertical code is written as `null`
+    pub fn to_json(&self) -> String {
+        match self.vertical {
+            Some(vc) => format!(r#"{{"horizontal":{},"vertical":{}}}"#, self.horizontal, vc),
+            None => format!(r#"{{"horizontal":{},"vertical":null}}"#, self.horizontal),
+        }
+    }
+
+    /// Serialize to a JSON object together with the path of the file the CRS was parsed from,
+    /// f.ex `{"path":"tile.laz","crs":{"horizontal":25832,"vertical":5941}}`
     ///
-    /// The validity of the extracted code is not checked, beyond checking that it is in [EPSG_RANGE].
-    /// Use the [crs-definitions](https://docs.rs/crs-definitions/latest/crs_definitions/) crate for checking the validity of a horizontal EPSG code.
+    /// The path is escaped as a JSON string, non-UTF-8 parts of it are replaced by `U+FFFD`
+    pub fn to_json_with_path(&self, path: &std::path::Path) -> String {
+        format!(
+            r#"{{"path":{},"crs":{}}}"#,
+            json_string(&path.to_string_lossy()),
+            self.to_json()
+        )
+    }
+
+    /// Get the approximate area of use of the horizontal CRS as `[west, south, east, north]`
+    /// in WGS 84 degrees, f.ex to check that the coordinates of a tile plausibly fall within the CRS
     ///
-    /// # Example
+    /// The area is derived from the zone of WGS 84 and ETRS89 UTM codes (see [Self::utm_zone]),
+    /// with ETRS89 limited to the latitudes of its European extent, and known for
+    /// WGS 84 (EPSG:4326) and ETRS89 (EPSG:4258). Returns `None` for other codes
+    pub fn area_of_use_wgs84(&self) -> Option<[f64; 4]> {
+        // the European extent of ETRS89
+        const ETRS89: [f64; 4] = [-16.1, 32.88, 40.18, 84.73];
+
+        match self.horizontal {
+            4326 => return Some([-180.0, -90.0, 180.0, 90.0]),
+            4258 => return Some(ETRS89),
+            _ => (),
+        }
+        let (zone, hemisphere) = self.utm_zone()?;
+        let west = -180.0 + 6.0 * (zone as f64 - 1.0);
+        let east = west + 6.0;
+        match (self.horizontal, hemisphere) {
+            (25828..=25838, _) => Some([west, ETRS89[1], east, ETRS89[3]]),
+            (_, Hemisphere::North) => Some([west, 0.0, east, 84.0]),
+            (_, Hemisphere::South) => Some([west, -80.0, east, 0.0]),
+        }
+    }
+
+    /// Whether the bounding box `(min_x, min_y, max_x, max_y)`, given in the coordinates of the CRS
+    /// (f.ex the bounds of a las header), plausibly lies within the extent of the horizontal CRS
     ///
-    /// ```
-    /// use las::Reader;
-    /// use las_crs::ParseEpsgCRS;
+    /// Meant for detecting files whose CRS and point coordinates are inconsistent, the extents are
+    /// rounded outwards so only clearly wrong combinations give `Some(false)`.
+    /// The extent is known for WGS 84 and ETRS89 (see [Self::area_of_use_wgs84]), their UTM zones
+    /// and the national grids in [NATIONAL_GRID_EXTENTS]. Returns `None` for other codes
+    pub fn is_in_region(&self, bbox: (f64, f64, f64, f64)) -> Option<bool> {
+        let extent = match self.horizontal {
+            4326 | 4258 => self.area_of_use_wgs84()?,
+            code => match self.utm_zone() {
+                // false eastings keep UTM coordinates within 0-1000km of the zone,
+                // and the southern hemisphere has a false northing of 10000km
+                Some((_, Hemisphere::North)) => [0.0, 0.0, 1_000_000.0, 9_400_000.0],
+                Some((_, Hemisphere::South)) => [0.0, 1_000_000.0, 1_000_000.0, 10_000_000.0],
+                None => NATIONAL_GRID_EXTENTS
+                    .iter()
+                    .find(|(c, _)| *c == code)
+                    .map(|(_, extent)| *extent)?,
+            },
+        };
+        let (min_x, min_y, max_x, max_y) = bbox;
+        Some(min_x >= extent[0] && min_y >= extent[1] && max_x <= extent[2] && max_y <= extent[3])
+    }
+
+    /// Get the UTM zone number and hemisphere of the horizontal CRS, for f.ex tiling and naming
     ///
-    /// let reader = Reader::from_path("testdata/autzen.las").expect("Cannot open reader");
-    /// let epsg = reader.header().get_epsg_crs().expect("Cannot parse EPSG code(s) from the CRS-(E)VLR(s)").expect("The Lidar file had no CRS");
-    /// ```
-    fn get_epsg_crs(&self) -> Result<Option<EpsgCRS>> {
-        if let Some(wkt) = self.get_wkt_crs_bytes() {
-            if !self.has_wkt_crs() {
-                log!(
-                    Level::Warn,
-                    "WKT CRS (E)VLR found, but header says it does not exist"
-                );
-            }
-            Ok(Some(get_epsg_from_wkt_crs_bytes(wkt)?))
-        } else if let Some(geotiff) = self.get_geotiff_crs()? {
-            if self.has_wkt_crs() {
-                log!(
-                    Level::Warn,
-                    "Only Geotiff CRS (E)VLR(s) found, but header says WKT exists"
-                );
-            }
-            Ok(Some(get_epsg_from_geotiff_crs(&geotiff)?))
-        } else {
-            if self.has_wkt_crs() {
-                log!(
-                    Level::Warn,
-                    "No CRS (E)VLR(s) found, but header says WKT exists"
-                );
-            }
-            Ok(None)
+    /// Recognizes the WGS 84 (EPSG:32601-32660 and 32701-32760) and
+    /// ETRS89 (EPSG:25828-25838) UTM codes, returns `None` for other codes
+    pub fn utm_zone(&self) -> Option<(u8, Hemisphere)> {
+        match self.horizontal {
+            c @ 32601..=32660 => Some(((c - 32600) as u8, Hemisphere::North)),
+            c @ 32701..=32760 => Some(((c - 32700) as u8, Hemisphere::South)),
+            c @ 25828..=25838 => Some(((c - 25800) as u8, Hemisphere::North)),
+            _ => None,
         }
     }
 }
 
-/// Tries to parse EPSG code(s) from WKT-CRS bytes.
-///
-/// By parsing the EPSG codes at the end of the vertical and horizontal CRS sub-strings
-/// This is not true WKT parser and might provide a bad code if
-/// the WKT-CRS bytes does not look as expected
-pub fn get_epsg_from_wkt_crs_bytes(bytes: &[u8]) -> Result<EpsgCRS> {
-    let wkt = String::from_utf8_lossy(bytes);
-
-    enum WktPieces<'a> {
-        One(&'a [u8]),
-        Two(&'a [u8], &'a [u8]),
-    }
-
-    impl WktPieces<'_> {
-        fn parse_codes(&self) -> (u16, u16) {
-            match self {
-                WktPieces::One(hor) => (Self::get_code(hor), 0),
-                WktPieces::Two(hor, ver) => (Self::get_code(hor), Self::get_code(ver)),
-            }
-        }
-
-        fn get_code(bytes: &[u8]) -> u16 {
-            // the EPSG code is located at the end of the substrings
-            // and so we iterate through the substrings backwards collecting
-            // digits and adding them to our EPSG code
-            let mut epsg_code = 0;
-            let mut code_has_started = false;
-            let mut power = 1;
-            // the 10 last bytes should be enough (with a small margin)
-            // as the code is 4 or 5 digits starting at the 2nd or 3rd byte from the back
-            for byte in bytes.trim_ascii_end().iter().rev().take(10) {
-                // if the byte is an ASCII encoded digit
-                if byte.is_ascii_digit() {
-                    // mark that the EPSG code has started
-                    // so that we can break when we no
-                    // longer find digits
-                    code_has_started = true;
-
-                    // translate from ASCII to digits
-                    // and multiply by powers of 10
-                    // sum it to build the EPSG
-                    // code digit by digit
-                    epsg_code += power * (byte - 48) as u16;
-                    power *= 10;
-                } else if code_has_started {
-                    // we no longer see digits
-                    // so the code must be over
-                    break;
-                }
-            }
-            epsg_code
+/// The approximate extents of national grid CRS's as `[min_x, min_y, max_x, max_y]` in the units of the CRS,
+/// used by [EpsgCRS::is_in_region]. The extents are the EPSG areas of use rounded outwards
+pub const NATIONAL_GRID_EXTENTS: &[(u16, [f64; 4])] = &[
+    // OSGB 1936 / British National Grid
+    (27700, [-110_000.0, -20_000.0, 700_000.0, 1_260_000.0]),
+    // RGF93 / Lambert-93
+    (2154, [0.0, 6_000_000.0, 1_300_000.0, 7_200_000.0]),
+    // ETRS89 / TM35FIN(E,N)
+    (3067, [0.0, 6_300_000.0, 800_000.0, 7_800_000.0]),
+    // SWEREF99 TM
+    (3006, [150_000.0, 6_100_000.0, 900_000.0, 7_700_000.0]),
+];
+
+/// The hemisphere of a UTM zone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hemisphere {
+    North,
+    South,
+}
+
+/// Quote and escape the string as a JSON string
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
         }
     }
+    json.push('"');
+    json
+}
 
-    // VERT_CS for WKT v1 and VERTCRS or VERTICALCRS for v2
-    let pieces = if let Some((horizontal, vertical)) = wkt.split_once("VERTCRS") {
-        WktPieces::Two(horizontal.as_bytes(), vertical.as_bytes())
-    } else if let Some((horizontal, vertical)) = wkt.split_once("VERTICALCRS") {
-        WktPieces::Two(horizontal.as_bytes(), vertical.as_bytes())
-    } else if let Some((horizontal, vertical)) = wkt.split_once("VERT_CS") {
-        WktPieces::Two(horizontal.as_bytes(), vertical.as_bytes())
-    } else {
-        WktPieces::One(wkt.as_bytes())
-    };
+/// Category of a vertical CRS, see [EpsgCRS::vertical_category]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerticalCategory {
+    /// Heights above mean sea level
+    MeanSeaLevel,
+    /// Heights above the ellipsoid
+    EllipsoidalHeight,
+    /// Heights above a global geoid model
+    Geoid,
+    /// Heights above a local datum
+    LocalDatum,
+    /// The code is not in a known range
+    Unknown,
+}
 
-    let codes = pieces.parse_codes();
-    let mut code = EpsgCRS {
-        horizontal: codes.0,
-        vertical: Some(codes.1),
-    };
+/// Combine the horizontal code of the left operand with the vertical code of the right operand,
+/// f.ex `horizontal_crs | vertical_crs` gives a compound CRS
+///
+/// Returns [Error::MissingVerticalCrs] if the right operand has no vertical code
+impl std::ops::BitOr for EpsgCRS {
+    type Output = Result<EpsgCRS>;
 
-    if !EPSG_RANGE.contains(&code.horizontal) {
-        return Err(Error::BadHorizontalCodeParsed(code));
+    fn bitor(self, rhs: EpsgCRS) -> Self::Output {
+        match rhs.vertical {
+            Some(vc) => self | vc,
+            None => Err(Error::MissingVerticalCrs),
+        }
     }
-    if let Some(v_code) = code.vertical
-        && !EPSG_RANGE.contains(&v_code)
-    {
-        code.vertical = None;
+}
+
+/// Set the right operand as the vertical code, the code is checked against EPSG_RANGE
+impl std::ops::BitOr<u16> for EpsgCRS {
+    type Output = Result<EpsgCRS>;
+
+    fn bitor(self, rhs: u16) -> Self::Output {
+        self.with_vertical_option(Some(rhs))
     }
-    Ok(code)
 }
 
-/// Get the EPSG code(s) from GeoTiff-CRS-data
-/// Only handles geotiff u16 data
-/// Returns ascii and double defined crs data in an [Error::UnimplementedForGeoTiffStringAndDoubleData]
-pub fn get_epsg_from_geotiff_crs(geotiff_crs_data: &GeoTiffCrs) -> Result<EpsgCRS> {
-    let mut out = (0, None);
-    for entry in geotiff_crs_data.entries.iter() {
-        match entry.id {
-            // 2048 and 3072 should not co-exist, but might both be combined with 4096
-            // 1024 should always exist
-            1024 => match &entry.data {
-                GeoTiffData::U16(0) => (), // should really not be zero, but let's rather error out later just in case
-                GeoTiffData::U16(1) => (), // projected crs
-                GeoTiffData::U16(2) => (), // geographic crs
-                GeoTiffData::U16(3) => (), // geographic + a vertical crs
-                GeoTiffData::U16(32_767) => return Err(Error::UserDefinedCrs),
-                _ => {
-                    return Err(Error::UnimplementedForGeoTiffStringAndDoubleData(
-                        entry.data.clone(),
-                    ));
-                }
-            },
-            2048 | 3072 => {
-                if let GeoTiffData::U16(v) = entry.data {
-                    out.0 = v;
-                }
-            }
-            4096 => {
-                // vertical crs
-                if let GeoTiffData::U16(v) = entry.data {
-                    out.1 = Some(v);
-                }
-            }
-            _ => (), // the rest are descriptions and units.
-        }
+/// The CRS names recognized by [EpsgCRS::from_projection_name] and their EPSG codes,
+/// the names are the EPSG registry names.
+/// UTM zones are not listed, they are recognized by pattern
+pub const PROJECTION_NAMES: &[(&str, u16)] = &[
+    ("WGS 84", 4326),
+    ("WGS 84 / Pseudo-Mercator", 3857),
+    ("ETRS89", 4258),
+    ("NAD83", 4269),
+    ("NAD27", 4267),
+    ("GDA2020", 7844),
+    ("NAD83 / Oregon GIC Lambert (ft)", 2992),
+    ("NAD83(HARN) / Oregon GIC Lambert (ft)", 2994),
+    ("OSGB 1936 / British National Grid", 27700),
+    ("RGF93 / Lambert-93", 2154),
+    ("RGF93 v1 / Lambert-93", 2154),
+    ("ETRS89 / TM35FIN(E,N)", 3067),
+    ("SWEREF99 TM", 3006),
+    ("NZGD2000 / New Zealand Transverse Mercator 2000", 2193),
+];
+
+/// Vertical CRS's common in lidar data and their EPSG registry names
+///
+/// The crs-definitions registry only holds horizontal CRS's, so the `validate` feature
+/// uses this table to recognize vertical codes, f.ex in [EpsgCRS::new_typed_checked]
+pub const VERTICAL_CRS_NAMES: &[(u16, &str)] = &[
+    (5941, "NN2000 height"),
+    (5776, "NN54 height"),
+    (5703, "NAVD88 height"),
+    (6360, "NAVD88 height (ftUS)"),
+    (5701, "ODN height"),
+    (5720, "NGF-IGN69 height"),
+    (5799, "DVR90 height"),
+    (5613, "RH2000 height"),
+    (3900, "N2000 height"),
+    (7837, "DHHN2016 height"),
+    (5783, "DHHN92 height"),
+    (5709, "NAP height"),
+    (5773, "EGM96 height"),
+    (3855, "EGM2008 height"),
+    (5798, "EGM84 height"),
+];
+
+/// Deprecated EPSG codes common in lidar data and their successors, used by [EpsgCRS::is_deprecated]
+///
+/// The unofficial web mercator code 900913 is not listed, as it does not fit in a `u16`
+pub const DEPRECATED_CODES: &[(u16, u16)] = &[
+    // Popular Visualisation CRS / Mercator -> WGS 84 / Pseudo-Mercator
+    (3785, 3857),
+    // WGS 84 / Plate Carree -> WGS 84 / World Equidistant Cylindrical
+    (32662, 4087),
+    // US National Atlas Equal Area -> NAD27 / US National Atlas Equal Area
+    (2163, 9311),
+    // WGS 84 / PDC Mercator -> WGS 84 / PDC Mercator
+    (3349, 3832),
+    // WGS 84 / Mercator 41 -> WGS 84 / Mercator 41
+    (3752, 3994),
+    // NAD83(CSRS98) -> NAD83(CSRS)
+    (4140, 4617),
+];
+
+/// The successor of the code if it is in [DEPRECATED_CODES]
+fn deprecated_code_successor(code: u16) -> Option<u16> {
+    DEPRECATED_CODES
+        .iter()
+        .find(|(deprecated, _)| *deprecated == code)
+        .map(|(_, successor)| *successor)
+}
+
+/// The horizontal EPSG codes of the national grids used for lidar data by country,
+/// keyed by ISO 3166-1 alpha-2 code, used by [EpsgCRS::is_national_grid]
+///
+/// Countries using several UTM zones list all the zones covering the country
+pub const NATIONAL_GRIDS: &[(&str, &[u16])] = &[
+    // ETRS89 / UTM zones 32N-33N
+    ("DE", &[25832, 25833]),
+    // ETRS89 / UTM zones 32N, 33N and 35N
+    ("NO", &[25832, 25833, 25835]),
+    // ETRS89 / UTM zone 32N
+    ("DK", &[25832]),
+    // SWEREF99 TM
+    ("SE", &[3006]),
+    // ETRS89 / TM35FIN(E,N)
+    ("FI", &[3067]),
+    // OSGB 1936 / British National Grid
+    ("GB", &[27700]),
+    // IRENET95 / Irish Transverse Mercator
+    ("IE", &[2157]),
+    // RGF93 / Lambert-93
+    ("FR", &[2154]),
+    // ETRS89 / UTM zones 29N-31N
+    ("ES", &[25829, 25830, 25831]),
+    // ETRS89 / Portugal TM06
+    ("PT", &[3763]),
+    // Amersfoort / RD New
+    ("NL", &[28992]),
+    // Belge 1972 / Belgian Lambert 72 and ETRS89 / Belgian Lambert 2008
+    ("BE", &[31370, 3812]),
+    // CH1903+ / LV95
+    ("CH", &[2056]),
+    // ETRS89 / Poland CS92
+    ("PL", &[2180]),
+    // NZGD2000 / New Zealand Transverse Mercator 2000
+    ("NZ", &[2193]),
+];
+
+/// Lowercase and strip all whitespace, so `"WGS84"` and `"wgs 84"` compare equal
+fn normalize_projection_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// The EPSG code of a normalized `"<datum>/utmzone<zone><n|s>"` name,
+/// for the WGS 84 (1-60 N and S), ETRS89 (28-38 N) and NAD83 (1-23 N) UTM zones
+fn utm_code_from_name(name: &str) -> Option<u16> {
+    let (datum, zone) = name.split_once("/utmzone")?;
+    let (zone, north) = match (zone.strip_suffix('n'), zone.strip_suffix('s')) {
+        (Some(zone), _) => (zone, true),
+        (_, Some(zone)) => (zone, false),
+        _ => return None,
+    };
+    let zone: u16 = zone.parse().ok()?;
+    match (datum, north) {
+        ("wgs84", true) if (1..=60).contains(&zone) => Some(32600 + zone),
+        ("wgs84", false) if (1..=60).contains(&zone) => Some(32700 + zone),
+        ("etrs89", true) if (28..=38).contains(&zone) => Some(25800 + zone),
+        ("nad83", true) if (1..=23).contains(&zone) => Some(26900 + zone),
+        _ => None,
     }
+}
 
-    if out.0 == 0 {
-        Err(las::Error::UnreadableGeoTiffCrs)?
+/// Same as [EpsgCRS::as_tuple]
+impl From<EpsgCRS> for (u16, Option<u16>) {
+    fn from(crs: EpsgCRS) -> Self {
+        crs.as_tuple()
     }
+}
 
-    let mut code = EpsgCRS {
-        horizontal: out.0,
-        vertical: out.1,
-    };
+/// Same as [EpsgCRS::new], both components are checked against EPSG_RANGE
+impl TryFrom<(u16, Option<u16>)> for EpsgCRS {
+    type Error = Error;
 
-    if !EPSG_RANGE.contains(&code.horizontal) {
-        return Err(Error::BadHorizontalCodeParsed(code));
+    fn try_from((horizontal, vertical): (u16, Option<u16>)) -> Result<Self> {
+        EpsgCRS::new(horizontal, vertical)
     }
-    if let Some(v_code) = code.vertical
-        && !EPSG_RANGE.contains(&v_code)
+}
+
+/// Builder assembling an [EpsgCRS] from codes gathered from separate sources,
+/// f.ex the horizontal code from a file and the vertical code from a project setting
+///
+/// The codes are checked against EPSG_RANGE by [Self::build]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EpsgCRSBuilder {
+    horizontal: Option<u16>,
+    vertical: Option<u16>,
+}
+
+impl EpsgCRSBuilder {
+    /// Construct a builder without any codes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// set the horizontal code
+    pub fn horizontal(mut self, horizontal_code: u16) -> Self {
+        self.horizontal = Some(horizontal_code);
+        self
+    }
+
+    /// set the vertical code if `Some`, or clear it if `None`
+    pub fn vertical(mut self, vertical_code: Option<u16>) -> Self {
+        self.vertical = vertical_code;
+        self
+    }
+
+    /// Build the EpsgCRS, both components are checked against EPSG_RANGE
+    ///
+    /// Returns [Error::MissingHorizontalCrs] if no horizontal code is set
+    pub fn build(&self) -> Result<EpsgCRS> {
+        let horizontal = self.horizontal.ok_or(Error::MissingHorizontalCrs)?;
+        EpsgCRS::new(horizontal, self.vertical)
+    }
+}
+
+/// The string formats recognized by [EpsgCRS::from_str_lenient]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrsStringFormat {
+    /// Bare integer code(s), f.ex `"4326"` or `"4326+5773"`
+    Bare,
+    /// `"EPSG:4326"`
+    EpsgPrefix,
+    /// `"EPSG::4326"`
+    EpsgDoubleColon,
+    /// OGC URN, f.ex `"urn:ogc:def:crs:EPSG::4326"`
+    OgcUrn,
+}
+
+/// Whether a CRS is a horizontal or a vertical reference system
+#[cfg(feature = "validate")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrsType {
+    /// Projected, geographic or geocentric CRS
+    Horizontal,
+    /// Vertical CRS
+    Vertical,
+}
+
+#[cfg(feature = "validate")]
+impl std::fmt::Display for CrsType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CrsType::Horizontal => write!(f, "horizontal"),
+            CrsType::Vertical => write!(f, "vertical"),
+        }
+    }
+}
+
+#[cfg(feature = "validate")]
+impl EpsgCRS {
+    /// Construct a new EpsgCrs checking both components against EPSG_RANGE
+    /// and the [crs-definitions](https://docs.rs/crs-definitions/latest/crs_definitions/) registry
+    ///
+    /// Returns [Error::WrongCrsType] if the horizontal code is a vertical CRS
+    /// or the vertical code is a horizontal CRS, which catches swapped codes.
+    /// The registry only holds horizontal CRS's, so vertical codes are recognized by [VERTICAL_CRS_NAMES].
+    /// Codes in neither are only range checked.
+    pub fn new_typed_checked(horizontal_code: u16, vertical_code: Option<u16>) -> Result<Self> {
+        let code = Self::new(horizontal_code, vertical_code)?;
+
+        if registry_crs_type(horizontal_code) == Some(CrsType::Vertical) {
+            return Err(Error::WrongCrsType {
+                code: horizontal_code,
+                expected: CrsType::Horizontal,
+            });
+        }
+        if let Some(vc) = vertical_code
+            && registry_crs_type(vc).is_some_and(|t| t != CrsType::Vertical)
+        {
+            return Err(Error::WrongCrsType {
+                code: vc,
+                expected: CrsType::Vertical,
+            });
+        }
+        Ok(code)
+    }
+
+    /// Describe the CRS with the names from the crs-definitions registry,
+    /// f.ex `"ETRS89 / UTM zone 32N (EPSG:25832) + NN2000 height (EPSG:5941)"`
+    ///
+    /// Horizontal codes are named by the registry and vertical codes by [VERTICAL_CRS_NAMES],
+    /// codes missing from both are described as `"EPSG:<code> (unknown)"`
+    pub fn describe(&self) -> String {
+        let describe_code = |code: u16| match registry_crs_name(code) {
+            Some(name) => format!("{name} (EPSG:{code})"),
+            None => format!("EPSG:{code} (unknown)"),
+        };
+        match self.vertical {
+            Some(vc) => format!("{} + {}", describe_code(self.horizontal), describe_code(vc)),
+            None => describe_code(self.horizontal),
+        }
+    }
+
+    /// Check that the horizontal CRS is projected, f.ex as a precondition of a pipeline stage
+    ///
+    /// Returns [Error::ExpectedProjected] if the horizontal code is a geographic or geocentric CRS
+    /// in the registry. Codes missing from the registry are accepted
+    pub fn require_projected(&self) -> Result<()> {
+        let Some(def) = crs_definitions::from_code(self.horizontal) else {
+            return Ok(());
+        };
+        let wkt = def.wkt.trim_start();
+        let is_projected = ["PROJCS", "PROJCRS", "PROJECTEDCRS"]
+            .iter()
+            .any(|keyword| wkt.starts_with(keyword));
+        if is_projected {
+            Ok(())
+        } else {
+            Err(Error::ExpectedProjected(self.horizontal))
+        }
+    }
+
+    /// Whether the CRSes have the same horizontal code and equivalent vertical codes,
+    /// treating the vertical codes of each `(a, b)` pair in `aliases` as equal in either order
+    ///
+    /// Useful for deduplicating tiles using different realizations of the same vertical datum.
+    /// The alias table is caller-supplied, as which codes are equivalent depends on the use case
+    pub fn vertically_equivalent(&self, other: &EpsgCRS, aliases: &[(u16, u16)]) -> bool {
+        if self.horizontal != other.horizontal {
+            return false;
+        }
+        match (self.vertical, other.vertical) {
+            (Some(a), Some(b)) => {
+                a == b || aliases.iter().any(|&pair| pair == (a, b) || pair == (b, a))
+            }
+            (a, b) => a == b,
+        }
+    }
+}
+
+#[cfg(feature = "proj")]
+impl EpsgCRS {
+    /// Construct a [proj::Proj] for the CRS, f.ex for reprojecting the points
+    ///
+    /// A compound CRS is given to PROJ as `EPSG:<horizontal>+<vertical>`.
+    /// Returns [Error::ProjCreateError] if PROJ cannot create the CRS
+    pub fn to_proj(&self) -> Result<proj::Proj> {
+        let definition = match self.vertical {
+            Some(vc) => format!("EPSG:{}+{vc}", self.horizontal),
+            None => format!("EPSG:{}", self.horizontal),
+        };
+        proj::Proj::new(&definition).map_err(|e| Error::ProjCreateError(e.to_string()))
+    }
+}
+
+/// An [EpsgCRS] whose horizontal code is guaranteed to exist in the
+/// [crs-definitions](https://docs.rs/crs-definitions/latest/crs_definitions/) registry
+///
+/// Construct it from an [EpsgCRS] with [TryFrom], which looks up the horizontal code in the registry.
+/// The registry only holds horizontal CRS's, so the vertical code is only checked against EPSG_RANGE
+#[cfg(feature = "validate")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckedCRS(EpsgCRS);
+
+#[cfg(feature = "validate")]
+impl CheckedCRS {
+    /// get the checked EpsgCRS
+    pub fn as_epsg_crs(&self) -> &EpsgCRS {
+        &self.0
+    }
+
+    /// get the horizontal code
+    pub fn get_horizontal(&self) -> u16 {
+        self.0.horizontal
+    }
+
+    /// get the optional vertical code
+    pub fn get_vertical(&self) -> Option<u16> {
+        self.0.vertical
+    }
+
+    /// set the horizontal code, the new code is looked up in the registry before setting
+    pub fn set_horizontal(&mut self, horizontal_code: u16) -> Result<()> {
+        check_registered(horizontal_code)?;
+        self.0.horizontal = horizontal_code;
+        Ok(())
+    }
+
+    /// set the vertical code, the new code is checked against EPSG_RANGE before setting
+    pub fn set_vertical(&mut self, vertical_code: u16) -> Result<()> {
+        self.0.set_vertical(vertical_code)
+    }
+
+    /// remove the vertical code
+    pub fn clear_vertical(&mut self) {
+        self.0.vertical = None;
+    }
+}
+
+#[cfg(feature = "validate")]
+impl TryFrom<EpsgCRS> for CheckedCRS {
+    type Error = Error;
+
+    /// Returns [Error::UnregisteredCode] if the horizontal code is missing from the registry
+    fn try_from(crs: EpsgCRS) -> Result<Self> {
+        check_registered(crs.horizontal)?;
+        Ok(CheckedCRS(crs))
+    }
+}
+
+#[cfg(feature = "validate")]
+impl From<CheckedCRS> for EpsgCRS {
+    fn from(crs: CheckedCRS) -> Self {
+        crs.0
+    }
+}
+
+/// Check that a code exists in the crs-definitions registry
+#[cfg(feature = "validate")]
+fn check_registered(code: u16) -> Result<()> {
+    match crs_definitions::from_code(code) {
+        Some(_) => Ok(()),
+        None => Err(Error::UnregisteredCode(code)),
+    }
+}
+
+/// Look up the name of a CRS in [VERTICAL_CRS_NAMES] or the crs-definitions registry
+///
+/// The registry name is the first quoted string of the registry WKT
+#[cfg(feature = "validate")]
+fn registry_crs_name(code: u16) -> Option<&'static str> {
+    if let Some((_, name)) = VERTICAL_CRS_NAMES.iter().find(|(c, _)| *c == code) {
+        return Some(name);
+    }
+    let def = crs_definitions::from_code(code)?;
+    let (_, rest) = def.wkt.split_once('"')?;
+    rest.split_once('"').map(|(name, _)| name)
+}
+
+/// Look up the type of CRS a code is, codes in [VERTICAL_CRS_NAMES] are vertical
+/// and codes in the crs-definitions registry are horizontal
+#[cfg(feature = "validate")]
+fn registry_crs_type(code: u16) -> Option<CrsType> {
+    if VERTICAL_CRS_NAMES.iter().any(|(c, _)| *c == code) {
+        return Some(CrsType::Vertical);
+    }
+    let def = crs_definitions::from_code(code)?;
+    let wkt = def.wkt.trim_start();
+    if wkt.starts_with("VERT_CS") || wkt.starts_with("VERTCRS") || wkt.starts_with("VERTICALCRS") {
+        Some(CrsType::Vertical)
+    } else {
+        Some(CrsType::Horizontal)
+    }
+}
+
+/// Configurable validation policy for parsed CRS's
+///
+/// Use [CrsValidator::validate] to enforce project-specific requirements
+/// on the output of the parsers. The default policy rejects user-defined codes,
+/// does not require a vertical component and allows any horizontal code in [EPSG_RANGE]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrsValidator {
+    /// Accept the GeoTiff user-defined code (32767) as a component
+    pub allow_user_defined: bool,
+
+    /// Reject CRS's without a vertical component
+    pub require_vertical: bool,
+
+    /// The range the horizontal code must be in
+    pub allowed_horizontal_range: RangeInclusive<u16>,
+}
+
+impl Default for CrsValidator {
+    fn default() -> Self {
+        CrsValidator {
+            allow_user_defined: false,
+            require_vertical: false,
+            allowed_horizontal_range: EPSG_RANGE,
+        }
+    }
+}
+
+impl CrsValidator {
+    /// Check the CRS against the policy, returning the first violation found
+    pub fn validate(&self, crs: &EpsgCRS) -> Result<()> {
+        if !self.allow_user_defined
+            && (crs.horizontal == USER_DEFINED_CODE || crs.vertical == Some(USER_DEFINED_CODE))
+        {
+            return Err(Error::UserDefinedCrs);
+        }
+        if !self.allowed_horizontal_range.contains(&crs.horizontal) {
+            return Err(Error::HorizontalCodeNotAllowed(crs.horizontal));
+        }
+        if self.require_vertical && crs.vertical.is_none() {
+            return Err(Error::MissingVerticalCrs);
+        }
+        Ok(())
+    }
+}
+
+/// Error enum
+///
+/// The enum is non-exhaustive so that new variants can be added without breaking changes,
+/// matches on it must include a wildcard arm
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Error propagated from the Las lib
+    #[error(transparent)]
+    LasError(#[from] las::Error),
+    /// User defined CRS cannot be parsed
+    #[error("Parsing of User Defined CRS not implemented")]
+    UserDefinedCrs,
+    /// Could not parse the CRS from the WKT-data
+    #[error("Unable to parse the found WKT-CRS (E)VLR")]
+    UnreadableWktCrs,
+    /// The parsed horizontal code is outside of [EPSG_RANGE]
+    #[error("The parsed code for the horizontal component is outside of the EPSG-range")]
+    BadHorizontalCodeParsed(EpsgCRS),
+    /// Cannot parse EPSG code from ascii string or double data
+    #[error("The CRS parser does not handle CRS's defined by Geotiff String and Double data")]
+    UnimplementedForGeoTiffStringAndDoubleData(GeoTiffData),
+    /// The provided code for setting is outside of EPSG_RANGE
+    #[error("The provided code for setting is outside of EPSG_RANGE")]
+    SetBadCode(u16),
+    /// The EPSG CRS is outside of EPSG_RANGE
+    #[error("A component of the EPSG code is outside of EPSG_RANGE")]
+    BadEPSGCrs,
+    /// The horizontal code is outside of the range allowed by a [CrsValidator]
+    #[error("The horizontal code {0} is outside of the allowed range")]
+    HorizontalCodeNotAllowed(u16),
+    /// The user id and record id given to [write_vlr_bytes_to_header] are not those of a CRS (E)VLR
+    #[error("The (E)VLR with user id {user_id} and record id {record_id} is not a CRS (E)VLR")]
+    NotCrsVlr { user_id: String, record_id: u16 },
+    /// The WKT-CRS has CRS nodes of both WKT1 and WKT2, so the parsed code could be wrong
+    #[error("The WKT-CRS mixes WKT1 and WKT2 CRS nodes")]
+    MixedWktDialects,
+    /// [EpsgCRSBuilder::build] was called without a horizontal code
+    #[error("A horizontal component is required, but none is set")]
+    MissingHorizontalCrs,
+    /// A [CrsValidator] requires a vertical component, but none is present
+    #[error("A vertical component is required, but the CRS has none")]
+    MissingVerticalCrs,
+    /// CRS (E)VLRs are present, but none of them could be read as a CRS
+    #[error("CRS (E)VLR(s) found, but no CRS could be read from them")]
+    MalformedCrsVlr,
+    /// The reader ended within the payload of a CRS (E)VLR, the partial payload is returned for recovery
+    #[error("The CRS (E)VLR with record id {record_id} is truncated")]
+    TruncatedCrsVlr { record_id: u16, data: Vec<u8> },
+    /// The string could not be parsed as an EPSG CRS
+    #[error("Unable to parse an EPSG CRS from the string: {0}")]
+    InvalidCrsString(String),
+    /// The parsed CRS was rejected by the predicate given to [ParseEpsgCRS::get_epsg_crs_filtered]
+    #[error("The parsed CRS {0:?} is not allowed")]
+    DisallowedCrs(EpsgCRS),
+    /// The GeoTiff code key does not match the model type, f.ex a projected model type with the code in key 2048
+    #[error("GeoTiff model type {model_type} does not match the code in key {key}")]
+    GeoTiffKeyModelMismatch { model_type: u16, key: u16 },
+    /// [EpsgCRS::replace_vertical] was called on a CRS without a vertical component
+    #[error("The CRS has no vertical component to replace")]
+    NoVerticalToReplace,
+    /// The WKT-CRS and the GeoTiff-CRS of the same header give different codes, see [validate_crs_consistency]
+    #[error("The WKT-CRS {wkt:?} and the GeoTiff-CRS {geotiff:?} disagree")]
+    CrsSourcesDisagree { wkt: EpsgCRS, geotiff: EpsgCRS },
+    /// The header has no CRS (E)VLRs, but a CRS is required
+    #[error("No CRS (E)VLR(s) found")]
+    MissingCrs,
+    /// PROJ could not create the CRS, the message is the PROJ error
+    #[cfg(feature = "proj")]
+    #[error("PROJ could not create the CRS: {0}")]
+    ProjCreateError(String),
+    /// [write_vlr_bytes_to_header] was given a payload too large for a VLR, but the LAS version has no EVLRs
+    #[error("The payload is too large for a VLR and LAS {major}.{minor} does not support EVLRs")]
+    EvlrsNotSupported { major: u8, minor: u8 },
+    /// An EPSG code could not be parsed as an integer
+    #[error(transparent)]
+    CodeParseError(#[from] std::num::ParseIntError),
+    /// The code is registered as a different type of CRS than expected
+    #[cfg(feature = "validate")]
+    #[error("EPSG:{code} is not a {expected} CRS")]
+    WrongCrsType { code: u16, expected: CrsType },
+    /// [EpsgCRS::require_projected] found a horizontal code that is not a projected CRS
+    #[cfg(feature = "validate")]
+    #[error("EPSG:{0} is not a projected CRS")]
+    ExpectedProjected(u16),
+    /// The code does not exist in the crs-definitions registry
+    #[cfg(feature = "validate")]
+    #[error("EPSG:{0} does not exist in the crs-definitions registry")]
+    UnregisteredCode(u16),
+}
+
+impl PartialEq for Error {
+    /// [Error::LasError]s are compared by their `Display` output and
+    /// [Error::UnimplementedForGeoTiffStringAndDoubleData] by its `Debug` output,
+    /// as the inner types do not implement `PartialEq`
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Error::LasError(a), Error::LasError(b)) => a.to_string() == b.to_string(),
+            (Error::BadHorizontalCodeParsed(a), Error::BadHorizontalCodeParsed(b)) => a == b,
+            (
+                Error::UnimplementedForGeoTiffStringAndDoubleData(a),
+                Error::UnimplementedForGeoTiffStringAndDoubleData(b),
+            ) => format!("{a:?}") == format!("{b:?}"),
+            (Error::SetBadCode(a), Error::SetBadCode(b)) => a == b,
+            (Error::InvalidCrsString(a), Error::InvalidCrsString(b)) => a == b,
+            (Error::DisallowedCrs(a), Error::DisallowedCrs(b)) => a == b,
+            (Error::CodeParseError(a), Error::CodeParseError(b)) => a == b,
+            #[cfg(feature = "proj")]
+            (Error::ProjCreateError(a), Error::ProjCreateError(b)) => a == b,
+            (
+                Error::GeoTiffKeyModelMismatch {
+                    model_type: a,
+                    key: a_key,
+                },
+                Error::GeoTiffKeyModelMismatch {
+                    model_type: b,
+                    key: b_key,
+                },
+            ) => a == b && a_key == b_key,
+            (
+                Error::TruncatedCrsVlr {
+                    record_id: a,
+                    data: a_data,
+                },
+                Error::TruncatedCrsVlr {
+                    record_id: b,
+                    data: b_data,
+                },
+            ) => a == b && a_data == b_data,
+            (Error::HorizontalCodeNotAllowed(a), Error::HorizontalCodeNotAllowed(b)) => a == b,
+            (
+                Error::EvlrsNotSupported {
+                    major: a,
+                    minor: a_minor,
+                },
+                Error::EvlrsNotSupported {
+                    major: b,
+                    minor: b_minor,
+                },
+            ) => a == b && a_minor == b_minor,
+            (
+                Error::NotCrsVlr {
+                    user_id: a,
+                    record_id: a_record_id,
+                },
+                Error::NotCrsVlr {
+                    user_id: b,
+                    record_id: b_record_id,
+                },
+            ) => a == b && a_record_id == b_record_id,
+            (
+                Error::CrsSourcesDisagree {
+                    wkt: a,
+                    geotiff: a_geotiff,
+                },
+                Error::CrsSourcesDisagree {
+                    wkt: b,
+                    geotiff: b_geotiff,
+                },
+            ) => a == b && a_geotiff == b_geotiff,
+            #[cfg(feature = "validate")]
+            (
+                Error::WrongCrsType {
+                    code: a,
+                    expected: a_expected,
+                },
+                Error::WrongCrsType {
+                    code: b,
+                    expected: b_expected,
+                },
+            ) => a == b && a_expected == b_expected,
+            #[cfg(feature = "validate")]
+            (Error::UnregisteredCode(a), Error::UnregisteredCode(b)) => a == b,
+            #[cfg(feature = "validate")]
+            (Error::ExpectedProjected(a), Error::ExpectedProjected(b)) => a == b,
+            // the remaining variants carry no data
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for Error {}
+
+pub trait ParseEpsgCRS {
+    /// Parse the EPSG code(s) of the CRS, `Ok(None)` if there is no CRS
+    fn get_epsg_crs(&self) -> Result<Option<EpsgCRS>>;
+
+    /// Same as [Self::get_epsg_crs], but returns [Error::DisallowedCrs] if the parsed CRS
+    /// is rejected by the `allowed` predicate, f.ex an organisation's allow-list of CRSes
+    fn get_epsg_crs_filtered<F: Fn(&EpsgCRS) -> bool>(&self, allowed: F) -> Result<Option<EpsgCRS>>
+    where
+        Self: Sized,
     {
-        code.vertical = None;
+        match self.get_epsg_crs()? {
+            Some(crs) if !allowed(&crs) => Err(Error::DisallowedCrs(crs)),
+            crs => Ok(crs),
+        }
+    }
+
+    /// Same as [Self::get_epsg_crs], but returns `None` on any error, f.ex
+    /// [Error::BadHorizontalCodeParsed] from the invalid CRS VLRs some writers add.
+    /// The error is logged at debug level
+    fn try_epsg_crs(&self) -> Option<EpsgCRS> {
+        match self.get_epsg_crs() {
+            Ok(crs) => crs,
+            Err(e) => {
+                log!(Level::Debug, "Unable to parse an EPSG CRS: {e}");
+                None
+            }
+        }
+    }
+}
+
+impl ParseEpsgCRS for Header {
+    /// Parse the EPSG coordinate reference system (CRSes) code(s) from the header.
+    ///
+    /// Las stores CRS-info in (E)VLRs either as Well Known Text (WKT) or in GeoTIff-format
+    /// **Most**, but not all, CRS' used for Aerial Lidar has an associated EPSG code.
+    /// Use this function to try and parse the EPSG code(s) from the header.
+    ///
+    /// WKT takes precedence over GeoTiff in this function, but they should not co-exist.
+    ///
+    /// The WKT-CRS is read from the `LASF_Projection` (E)VLR with record id 2112 and the GeoTiff-CRS
+    /// from the `LASF_Projection` (E)VLRs with record ids 34735-34737. The (E)VLRs are looked up by id,
+    /// so their position relative to other (E)VLRs, like the laszip VLR in .laz files, does not matter.
+    ///
+    /// Returns `Ok(None)` only if the header has no CRS-(E)VLRs at all, and [Error::MalformedCrsVlr]
+    /// if CRS-(E)VLRs are present but neither a WKT nor a GeoTiff CRS can be read from them.
+    ///
+    /// Just because this function fails does not mean that no CRS-data is available.
+    /// Use functions [Self::get_wkt_crs_bytes] or [Self::get_geotiff_crs] to get all data stored in the CRS-(E)VLRs.
+    ///
+    /// Parsing code(s) from WKT-CRS v1 or v2 and GeoTiff U16-data is supported.
+    ///
+    /// The validity of the extracted code is not checked, beyond checking that it is in [EPSG_RANGE].
+    /// Use the [crs-definitions](https://docs.rs/crs-definitions/latest/crs_definitions/) crate for checking the validity of a horizontal EPSG code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use las::Reader;
+    /// use las_crs::ParseEpsgCRS;
+    ///
+    /// let reader = Reader::from_path("testdata/autzen.las").expect("Cannot open reader");
+    /// let epsg = reader.header().get_epsg_crs().expect("Cannot parse EPSG code(s) from the CRS-(E)VLR(s)").expect("The Lidar file had no CRS");
+    /// ```
+    fn get_epsg_crs(&self) -> Result<Option<EpsgCRS>> {
+        if let Some(wkt) = self.get_wkt_crs_bytes() {
+            if !self.has_wkt_crs() {
+                log!(
+                    Level::Warn,
+                    "WKT CRS (E)VLR found, but header says it does not exist"
+                );
+            }
+            let mut code = get_epsg_from_wkt_crs_bytes(wkt)?;
+            if code.vertical.is_none()
+                && let Some(vertical) = paired_vertical_wkt_code(self)
+            {
+                code.vertical = Some(vertical);
+            }
+            Ok(Some(code))
+        } else if let Some(geotiff) = self.get_geotiff_crs()? {
+            if let Some(directory) = self
+                .vlrs()
+                .iter()
+                .chain(self.evlrs())
+                .find(|vlr| vlr.user_id == CRS_VLR_USER_ID && vlr.record_id == 34735)
+            {
+                check_geokey_directory_version(&geokey_directory_shorts(&directory.data));
+            }
+            if self.has_wkt_crs() {
+                log!(
+                    Level::Warn,
+                    "Only Geotiff CRS (E)VLR(s) found, but header says WKT exists"
+                );
+            }
+            Ok(Some(get_epsg_from_geotiff_crs(&geotiff)?))
+        } else if self.vlrs().iter().chain(self.evlrs()).any(is_crs_vlr) {
+            // f.ex GeoTiff double or ascii (E)VLRs without the key directory
+            Err(Error::MalformedCrsVlr)
+        } else {
+            if self.has_wkt_crs() {
+                log!(
+                    Level::Warn,
+                    "No CRS (E)VLR(s) found, but header says WKT exists"
+                );
+            }
+            Ok(None)
+        }
+    }
+}
+
+/// A header caching the result of parsing its CRS, for pipelines checking the CRS of the same file many times
+///
+/// The CRS is parsed on the first call to [Self::get_epsg_crs], the wrapper is `Sync`
+/// so it can be shared between threads, only one of which does the parsing
+#[derive(Debug)]
+pub struct WktCrsCached {
+    header: Header,
+    crs: OnceLock<Result<Option<EpsgCRS>>>,
+}
+
+impl WktCrsCached {
+    /// Wrap the header, nothing is parsed until [Self::get_epsg_crs] is called
+    pub fn new(header: Header) -> Self {
+        WktCrsCached {
+            header,
+            crs: OnceLock::new(),
+        }
+    }
+
+    /// get the wrapped header
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// unwrap the header, discarding the cached result
+    pub fn into_header(self) -> Header {
+        self.header
+    }
+
+    /// Same as [ParseEpsgCRS::get_epsg_crs], but only parses the header on the first call
+    ///
+    /// A reference to the cached result is returned, as [Error] cannot be cloned
+    pub fn get_epsg_crs(&self) -> &Result<Option<EpsgCRS>> {
+        self.crs.get_or_init(|| self.header.get_epsg_crs())
+    }
+}
+
+/// Copy the CRS (E)VLRs of a header to a builder, f.ex for giving a new file the CRS of a reference file
+pub trait CopyVlr {
+    fn copy_crs_vlrs_to(&self, builder: &mut Builder) -> Result<()>;
+}
+
+impl CopyVlr for Header {
+    /// Copy the WKT-CRS and GeoTiff-CRS (E)VLRs byte-for-byte, without parsing or re-encoding them,
+    /// and the `has_wkt_crs` flag
+    ///
+    /// CRS (E)VLRs already in the builder are replaced, so the builder ends up with exactly the CRS of the header.
+    /// Returns [Error::MissingCrs] (leaving the builder unchanged) if the header has no CRS (E)VLRs
+    fn copy_crs_vlrs_to(&self, builder: &mut Builder) -> Result<()> {
+        if !self.vlrs().iter().chain(self.evlrs()).any(is_crs_vlr) {
+            return Err(Error::MissingCrs);
+        }
+
+        builder.vlrs.retain(|vlr| !is_crs_vlr(vlr));
+        builder.evlrs.retain(|evlr| !is_crs_vlr(evlr));
+        builder
+            .vlrs
+            .extend(self.vlrs().iter().filter(|vlr| is_crs_vlr(vlr)).cloned());
+        builder
+            .evlrs
+            .extend(self.evlrs().iter().filter(|evlr| is_crs_vlr(evlr)).cloned());
+        builder.has_wkt_crs = self.has_wkt_crs();
+        Ok(())
+    }
+}
+
+/// Parse the EPSG code(s) from PDAL/entwine JSON metadata, f.ex the output of `pdal info --metadata`
+///
+/// The WKT at `metadata.srs.wkt` is parsed if present and non-empty, otherwise the code at
+/// `metadata.srs.epsg`, either a number or a string like `"EPSG:25832"`.
+/// Returns `Ok(None)` if neither is present
+#[cfg(feature = "serde")]
+impl ParseEpsgCRS for serde_json::Value {
+    fn get_epsg_crs(&self) -> Result<Option<EpsgCRS>> {
+        let srs = &self["metadata"]["srs"];
+        if let Some(wkt) = srs["wkt"].as_str()
+            && !wkt.is_empty()
+        {
+            return get_epsg_from_wkt_crs_bytes(wkt.as_bytes()).map(Some);
+        }
+
+        let epsg = &srs["epsg"];
+        if epsg.is_null() {
+            Ok(None)
+        } else if let Some(code) = epsg.as_u64() {
+            let code =
+                u16::try_from(code).map_err(|_| Error::InvalidCrsString(code.to_string()))?;
+            EpsgCRS::new(code, None).map(Some)
+        } else if let Some(code) = epsg.as_str() {
+            EpsgCRS::from_str_lenient(code).map(Some)
+        } else {
+            Err(Error::InvalidCrsString(epsg.to_string()))
+        }
+    }
+}
+
+/// Open a lidar file and parse the EPSG code(s) from its header with [ParseEpsgCRS::get_epsg_crs]
+///
+/// Opening .laz files requires the laz feature
+pub fn get_epsg_crs_from_path(path: impl AsRef<std::path::Path>) -> Result<Option<EpsgCRS>> {
+    let reader = las::Reader::from_path(path)?;
+    reader.header().get_epsg_crs()
+}
+
+/// Parse only the vertical code of the CRS of the header, f.ex for workflows only concerned with heights
+///
+/// The horizontal CRS is not parsed, so a broken horizontal code does not hide a valid vertical code.
+/// The vertical code is read from the vertical node of the WKT-CRS (or a paired vertical WKT-CRS (E)VLR)
+/// or from key 4096 of the GeoTiff-CRS. Returns `Ok(None)` for horizontal-only CRS's, CRS-less headers
+/// and vertical codes outside of [EPSG_RANGE], and an error only if the GeoTiff-CRS (E)VLRs cannot be read
+pub fn get_vertical_epsg(header: &Header) -> Result<Option<u16>> {
+    if let Some(wkt) = header.get_wkt_crs_bytes() {
+        return Ok(get_vertical_epsg_from_wkt(wkt).or_else(|| paired_vertical_wkt_code(header)));
+    }
+    let Some(geotiff) = header.get_geotiff_crs()? else {
+        return Ok(None);
+    };
+    Ok(geotiff
+        .entries
+        .iter()
+        .find_map(|entry| match (entry.id, &entry.data) {
+            (4096, GeoTiffData::U16(v)) if EPSG_RANGE.contains(v) => Some(*v),
+            _ => None,
+        }))
+}
+
+/// Same as [ParseEpsgCRS::get_epsg_crs], but falls back to the GeoTiff-CRS if parsing the WKT-CRS fails
+///
+/// Some files contain a broken WKT-CRS (E)VLR (f.ex truncated by a writer bug) alongside a valid GeoTiff-CRS.
+/// If the WKT-CRS gives [Error::UnreadableWktCrs] or [Error::BadHorizontalCodeParsed] the GeoTiff-CRS is tried,
+/// and if that is missing or fails as well the WKT error is returned
+pub fn get_epsg_crs_with_fallback(header: &Header) -> Result<Option<EpsgCRS>> {
+    match header.get_epsg_crs() {
+        Err(e @ (Error::UnreadableWktCrs | Error::BadHorizontalCodeParsed(_))) => {
+            match header.get_geotiff_crs() {
+                Ok(Some(geotiff)) => match get_epsg_from_geotiff_crs(&geotiff) {
+                    Ok(code) => {
+                        log!(
+                            Level::Warn,
+                            "Unable to parse the WKT-CRS ({e}), using the GeoTiff-CRS instead"
+                        );
+                        Ok(Some(code))
+                    }
+                    Err(_) => Err(e),
+                },
+                _ => Err(e),
+            }
+        }
+        result => result,
+    }
+}
+
+/// Parse both the WKT-CRS and the GeoTiff-CRS of the header and check that they agree,
+/// f.ex for catching corrupt dual-encoded files on ingestion
+///
+/// Returns [Error::CrsSourcesDisagree] if both are present and give different codes,
+/// and the error of the source that fails if either cannot be parsed.
+/// A header with only one of the sources is parsed as by [ParseEpsgCRS::get_epsg_crs],
+/// and a header without CRS (E)VLRs gives [Error::MissingCrs]
+pub fn validate_crs_consistency(header: &Header) -> Result<EpsgCRS> {
+    match (header.get_wkt_crs_bytes(), header.get_geotiff_crs()?) {
+        (Some(wkt), Some(geotiff)) => {
+            let mut wkt_crs = get_epsg_from_wkt_crs_bytes(wkt)?;
+            if wkt_crs.vertical.is_none() {
+                wkt_crs.vertical = paired_vertical_wkt_code(header);
+            }
+            let geotiff_crs = get_epsg_from_geotiff_crs(&geotiff)?;
+            if wkt_crs == geotiff_crs {
+                Ok(wkt_crs)
+            } else {
+                Err(Error::CrsSourcesDisagree {
+                    wkt: wkt_crs,
+                    geotiff: geotiff_crs,
+                })
+            }
+        }
+        _ => header.get_epsg_crs()?.ok_or(Error::MissingCrs),
+    }
+}
+
+/// Where in the header a CRS was parsed from, see [parse_all]
+///
+/// The user id and record id are those of the (E)VLR the code was read from,
+/// f.ex for debugging files using non-standard ids
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CrsSource {
+    /// The WKT-CRS (E)VLR
+    Wkt { user_id: String, record_id: u16 },
+    /// The code keys (2048, 3072 and 4096) of the GeoTiff key directory (E)VLR
+    GeoTiff { user_id: String, record_id: u16 },
+    /// `EPSG:<code>` tokens in the citation keys of the GeoTiff ascii params (E)VLR
+    GeoTiffCitation { user_id: String, record_id: u16 },
+}
+
+/// The user id and record id of the first (E)VLR of the header matching the predicate,
+/// or the standard CRS (E)VLR ids with the fallback record id if none matches
+fn source_vlr_ids(
+    header: &Header,
+    fallback_record_id: u16,
+    f: impl Fn(&Vlr) -> bool,
+) -> (String, u16) {
+    header
+        .vlrs()
+        .iter()
+        .chain(header.evlrs())
+        .find(|vlr| f(vlr))
+        .map(|vlr| (vlr.user_id.clone(), vlr.record_id))
+        .unwrap_or_else(|| (CRS_VLR_USER_ID.to_string(), fallback_record_id))
+}
+
+/// Try every parsing strategy on the header and return all the CRS's found with their source
+/// and a confidence score, sorted by descending confidence
+///
+/// The confidence is 1.0 for codes read from WKT authority nodes or GeoTiff code keys,
+/// 0.7 for WKT codes found by the trailing-digit heuristic and 0.5 for GeoTiff citation tokens.
+/// Strategies that fail are skipped, so the list is empty if no CRS could be parsed
+pub fn parse_all(header: &Header) -> Vec<(EpsgCRS, CrsSource, f32)> {
+    let mut results: Vec<(EpsgCRS, CrsSource, f32)> = Vec::new();
+
+    if let Some(wkt) = header.get_wkt_crs_bytes() {
+        let (crs, diagnostics) = get_epsg_from_wkt_crs_bytes_with_diagnostics(wkt);
+        if let Ok(mut crs) = crs {
+            if crs.vertical.is_none() {
+                crs.vertical = paired_vertical_wkt_code(header);
+            }
+            let confidence = if diagnostics.used_fallback_heuristic {
+                0.7
+            } else {
+                1.0
+            };
+            let (user_id, record_id) =
+                source_vlr_ids(header, 2112, |vlr| vlr.data.as_slice() == wkt);
+            results.push((crs, CrsSource::Wkt { user_id, record_id }, confidence));
+        }
+    }
+
+    if let Ok(Some(geotiff)) = header.get_geotiff_crs()
+        && let Ok(crs) = get_epsg_from_geotiff_crs(&geotiff)
+    {
+        let has_code_key = geotiff.entries.iter().any(|entry| {
+            matches!(entry.id, 2048 | 3072) && matches!(entry.data, GeoTiffData::U16(v) if v != 0)
+        });
+        if has_code_key {
+            let (user_id, record_id) = source_vlr_ids(header, 34735, |vlr| {
+                vlr.user_id == CRS_VLR_USER_ID && vlr.record_id == 34735
+            });
+            results.push((crs, CrsSource::GeoTiff { user_id, record_id }, 1.0));
+        } else {
+            let (user_id, record_id) = source_vlr_ids(header, 34737, |vlr| {
+                vlr.user_id == CRS_VLR_USER_ID && vlr.record_id == 34737
+            });
+            results.push((crs, CrsSource::GeoTiffCitation { user_id, record_id }, 0.5));
+        }
+    }
+
+    results.sort_by(|a, b| b.2.total_cmp(&a.2));
+    results
+}
+
+/// The parsed CRS together with the point count and bounds of a header, see [header_summary]
+#[derive(Debug, PartialEq)]
+pub struct HeaderSummary {
+    /// The result of [ParseEpsgCRS::get_epsg_crs]
+    pub crs: Result<Option<EpsgCRS>>,
+    /// The number of points in the file
+    pub point_count: u64,
+    /// The bounds of the points in the file
+    pub bounds: Bounds,
+}
+
+/// Parse the CRS and collect the point count and bounds of the header in one call,
+/// f.ex for cataloguing tiles
+pub fn header_summary(header: &Header) -> HeaderSummary {
+    HeaderSummary {
+        crs: header.get_epsg_crs(),
+        point_count: header.number_of_points(),
+        bounds: header.bounds(),
+    }
+}
+
+/// Count the distinct CRS's in the results of parsing a batch of files, f.ex for a report of a directory
+///
+/// CRS-less files are counted in the `None` bucket. Files that could not be parsed are not counted
+pub fn summarize_crs(
+    results: &[(PathBuf, Result<Option<EpsgCRS>>)],
+) -> HashMap<Option<EpsgCRS>, usize> {
+    let mut counts = HashMap::new();
+    for (_, result) in results {
+        if let Ok(crs) = result {
+            *counts.entry(*crs).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Remove the bogus WKT-CRS (E)VLR with EPSG code 0 that some writers add to CRS-less files
+/// (f.ex QGIS when converting to .copc.laz) and clear the `has_wkt_crs` flag of the header
+///
+/// Returns whether a repair happened. Other (E)VLRs, including GeoTiff-CRS (E)VLRs, are kept,
+/// so a correct CRS can be stamped on the header afterwards
+pub fn repair_copc_zero_crs(header: &mut Header) -> bool {
+    let is_bogus_wkt = |vlr: &Vlr| {
+        vlr.user_id == CRS_VLR_USER_ID
+            && vlr.record_id == 2112
+            && matches!(
+                get_epsg_from_wkt_crs_bytes(&vlr.data),
+                Err(Error::BadHorizontalCodeParsed(code)) if code.horizontal == 0
+            )
+    };
+    if !header.vlrs().iter().chain(header.evlrs()).any(is_bogus_wkt) {
+        return false;
+    }
+
+    let mut builder = Builder::from(header.clone());
+    builder.vlrs.retain(|vlr| !is_bogus_wkt(vlr));
+    builder.evlrs.retain(|evlr| !is_bogus_wkt(evlr));
+    builder.has_wkt_crs = false;
+    match builder.into_header() {
+        Ok(repaired) => {
+            *header = repaired;
+            true
+        }
+        Err(e) => {
+            log!(
+                Level::Warn,
+                "Unable to rebuild the header after repair: {e}"
+            );
+            false
+        }
+    }
+}
+
+/// Remove all CRS (E)VLRs (WKT-CRS and GeoTiff-CRS) from the header and clear the `has_wkt_crs` flag,
+/// f.ex for anonymizing files or re-deriving the CRS from scratch
+///
+/// Returns the number of (E)VLRs removed. The header is left unchanged if it cannot be rebuilt
+pub fn remove_all_crs_vlrs(header: &mut Header) -> usize {
+    let count = header
+        .vlrs()
+        .iter()
+        .chain(header.evlrs())
+        .filter(|vlr| is_crs_vlr(vlr))
+        .count();
+    if count == 0 && !header.has_wkt_crs() {
+        return 0;
+    }
+
+    let mut builder = Builder::from(header.clone());
+    builder.vlrs.retain(|vlr| !is_crs_vlr(vlr));
+    builder.evlrs.retain(|evlr| !is_crs_vlr(evlr));
+    builder.has_wkt_crs = false;
+    match builder.into_header() {
+        Ok(stripped) => {
+            *header = stripped;
+            count
+        }
+        Err(e) => {
+            log!(
+                Level::Warn,
+                "Unable to rebuild the header after removing the CRS (E)VLRs: {e}"
+            );
+            0
+        }
+    }
+}
+
+/// Add a pre-formed CRS (E)VLR payload to the builder, f.ex copied from another file,
+/// without parsing it
+///
+/// The user id must be `LASF_Projection` and the record id one of the CRS record ids
+/// (2111, 2112, 34735, 34736 or 34737), otherwise [Error::NotCrsVlr] is returned.
+/// An existing (E)VLR with the same ids is replaced, payloads too large for a VLR are added as an EVLR,
+/// and `has_wkt_crs` is set when adding a WKT-CRS (record id 2112).
+/// EVLRs were added in LAS 1.4, so for older versions [Error::EvlrsNotSupported] is returned
+/// for payloads too large for a VLR, and the builder is left unchanged
+pub fn write_vlr_bytes_to_header(
+    builder: &mut Builder,
+    user_id: &str,
+    record_id: u16,
+    data: Vec<u8>,
+) -> Result<()> {
+    let description = match (user_id, record_id) {
+        (CRS_VLR_USER_ID, 2111) => "OGC Math Transform WKT",
+        (CRS_VLR_USER_ID, 2112) => "OGC Coordinate System WKT",
+        (CRS_VLR_USER_ID, 34735) => "GeoTiff GeoKeyDirectoryTag",
+        (CRS_VLR_USER_ID, 34736) => "GeoTiff GeoDoubleParamsTag",
+        (CRS_VLR_USER_ID, 34737) => "GeoTiff GeoAsciiParamsTag",
+        _ => {
+            return Err(Error::NotCrsVlr {
+                user_id: user_id.to_string(),
+                record_id,
+            });
+        }
+    };
+
+    let fits_in_vlr = data.len() <= u16::MAX as usize;
+    let version = builder.version;
+    if !fits_in_vlr && (version.major, version.minor) < (1, 4) {
+        return Err(Error::EvlrsNotSupported {
+            major: version.major,
+            minor: version.minor,
+        });
+    }
+
+    let same_ids = |vlr: &Vlr| vlr.user_id == user_id && vlr.record_id == record_id;
+    builder.vlrs.retain(|vlr| !same_ids(vlr));
+    builder.evlrs.retain(|evlr| !same_ids(evlr));
+
+    let vlr = Vlr {
+        user_id: user_id.to_string(),
+        record_id,
+        description: description.to_string(),
+        data,
+    };
+    if fits_in_vlr {
+        builder.vlrs.push(vlr);
+    } else {
+        builder.evlrs.push(vlr);
+    }
+    if record_id == 2112 {
+        builder.has_wkt_crs = true;
+    }
+    Ok(())
+}
+
+/// Find the vertical code in a standalone vertical WKT-CRS (E)VLR paired with the horizontal WKT-CRS
+///
+/// Some writers store a compound CRS as one WKT (E)VLR for the horizontal CRS and another
+/// `LASF_Projection` (E)VLR containing only the vertical CRS. Only an (E)VLR with the record id
+/// following the WKT-CRS (E)VLR, or one whose payload is a standalone vertical CRS node, is taken as the pair,
+/// so duplicated or unrelated WKT (E)VLRs do not supply the vertical code
+fn paired_vertical_wkt_code(header: &Header) -> Option<u16> {
+    let crs_vlrs = || {
+        header
+            .vlrs()
+            .iter()
+            .chain(header.evlrs())
+            .enumerate()
+            .filter(|(_, vlr)| vlr.user_id == CRS_VLR_USER_ID)
+    };
+    // the same (E)VLR as [Header::get_wkt_crs_bytes] finds
+    let (horizontal_index, horizontal) = crs_vlrs().find(|(_, vlr)| vlr.record_id == 2112)?;
+    crs_vlrs()
+        .filter(|(i, vlr)| {
+            *i != horizontal_index
+                && !matches!(vlr.record_id, 2111 | 34735..=34737)
+                && (vlr.record_id == horizontal.record_id + 1 || is_vertical_wkt(&vlr.data))
+        })
+        .find_map(|(_, vlr)| get_vertical_epsg_from_wkt(&vlr.data))
+}
+
+/// Whether the WKT-CRS bytes are a standalone vertical CRS node, f.ex `VERT_CS[..]`
+fn is_vertical_wkt(bytes: &[u8]) -> bool {
+    let Ok(bytes) = decompress_crs_payload(bytes) else {
+        return false;
+    };
+    let wkt = decode_vlr_bytes(&bytes);
+    DEFAULT_VERTICAL_KEYWORDS.contains(&wkt_node_keyword(wkt.trim_start()))
+}
+
+/// Parse the code of the vertical CRS node in WKT-CRS bytes, if any
+fn get_vertical_epsg_from_wkt(bytes: &[u8]) -> Option<u16> {
+    let bytes = decompress_crs_payload(bytes).ok()?;
+    let wkt = decode_vlr_bytes(&bytes);
+    let wkt = normalize_wkt_whitespace(&wkt);
+    let (_, vertical) = DEFAULT_VERTICAL_KEYWORDS
+        .iter()
+        .find_map(|keyword| split_at_wkt_keyword(&wkt, keyword))?;
+    let code = wkt_node_authority(vertical.as_bytes())
+        .unwrap_or_else(|| trailing_epsg_code(vertical.as_bytes()));
+    EPSG_RANGE.contains(&code).then_some(code)
+}
+
+/// The user id of the CRS (E)VLRs
+const CRS_VLR_USER_ID: &str = "LASF_Projection";
+
+/// The record ids of the CRS (E)VLRs, the WKT math transform and coordinate system
+/// and the GeoTiff key directory, double and ascii params
+const CRS_VLR_RECORD_IDS: [u16; 5] = [2111, 2112, 34735, 34736, 34737];
+
+/// Whether the (E)VLR is one of the CRS (E)VLRs
+fn is_crs_vlr(vlr: &Vlr) -> bool {
+    vlr.user_id == CRS_VLR_USER_ID && CRS_VLR_RECORD_IDS.contains(&vlr.record_id)
+}
+
+/// The magic bytes starting a zstd frame
+#[cfg(feature = "zstd")]
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+/// Decompress a zstd-compressed CRS (E)VLR payload, as written by some experimental tools.
+/// Payloads not starting with the zstd magic bytes are returned as is
+#[cfg(feature = "zstd")]
+fn decompress_crs_payload(bytes: &[u8]) -> Result<Cow<'_, [u8]>> {
+    if bytes.starts_with(&ZSTD_MAGIC) {
+        zstd::decode_all(bytes)
+            .map(Cow::Owned)
+            .map_err(|_| Error::UnreadableWktCrs)
+    } else {
+        Ok(Cow::Borrowed(bytes))
+    }
+}
+
+#[cfg(not(feature = "zstd"))]
+fn decompress_crs_payload(bytes: &[u8]) -> Result<Cow<'_, [u8]>> {
+    Ok(Cow::Borrowed(bytes))
+}
+
+/// Decode the bytes of a text (E)VLR, f.ex a WKT-CRS (E)VLR
+///
+/// The bytes are decoded as UTF-8 if valid, otherwise they are decoded as Latin-1 (ISO-8859-1),
+/// which some writers use for accented characters in CRS names
+pub fn decode_vlr_bytes(bytes: &[u8]) -> Cow<'_, str> {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => Cow::Borrowed(s),
+        // every byte is a valid Latin-1 code point with the same value as the char
+        Err(_) => Cow::Owned(bytes.iter().map(|&b| b as char).collect()),
+    }
+}
+
+/// WKT-CRS dialect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WktVersion {
+    /// WKT1, with keywords like `PROJCS`, `GEOGCS` and `VERT_CS`
+    Wkt1,
+    /// WKT2 (2015 or 2019), with keywords like `PROJCRS`, `GEOGCRS` and `VERTCRS`
+    Wkt2,
+}
+
+/// The keywords of WKT2 CRS nodes
+const WKT2_KEYWORDS: [&str; 7] = [
+    "PROJCRS",
+    "GEOGCRS",
+    "GEODCRS",
+    "GEODETICCRS",
+    "VERTCRS",
+    "VERTICALCRS",
+    "COMPOUNDCRS",
+];
+
+/// The keywords of WKT1 CRS nodes
+const WKT1_KEYWORDS: [&str; 5] = ["PROJCS", "GEOGCS", "GEOCCS", "VERT_CS", "COMPD_CS"];
+
+/// Whether the WKT has CRS nodes of both WKT1 and WKT2, f.ex a WKT1 `PROJCS` header
+/// concatenated with a WKT2 `PROJCRS` body by a broken writer
+fn has_mixed_wkt_dialects(wkt: &str) -> bool {
+    let has_node = |keywords: &[&str]| {
+        keywords
+            .iter()
+            .any(|keyword| split_at_wkt_keyword(wkt, keyword).is_some())
+    };
+    has_node(&WKT1_KEYWORDS) && has_node(&WKT2_KEYWORDS)
+}
+
+/// Detect which WKT dialect the WKT-CRS bytes are written in
+///
+/// Returns `None` if no known CRS keyword is found
+pub fn detect_wkt_version(bytes: &[u8]) -> Option<WktVersion> {
+    let wkt = decode_vlr_bytes(bytes);
+    if WKT2_KEYWORDS.iter().any(|k| wkt.contains(k)) {
+        Some(WktVersion::Wkt2)
+    } else if WKT1_KEYWORDS.iter().any(|k| wkt.contains(k)) {
+        Some(WktVersion::Wkt1)
+    } else {
+        None
+    }
+}
+
+/// Parse the EPSG code(s) from a lidar file through any `Read + Seek`,
+/// f.ex a [std::io::Cursor] over a memory-mapped slice of the file
+///
+/// Only the header and the VLRs are read, not the points, so the first `offset_to_point_data`
+/// bytes of the file (the header + VLRs) are enough when the CRS is stored in VLRs.
+/// The EVLRs of LAS 1.4 files are read if the reader reaches them, otherwise they are skipped with a warning.
+///
+/// If the reader ends within the payload of a CRS (E)VLR, f.ex in a corrupted file with
+/// a VLR count that overruns the data, [Error::TruncatedCrsVlr] is returned with the partial payload.
+///
+/// # Example
+///
+/// ```
+/// use std::io::Cursor;
+///
+/// let bytes = std::fs::read("testdata/autzen.las").expect("Cannot read file");
+/// let epsg = las_crs::get_epsg_crs_from_reader(Cursor::new(&bytes[..1994]))
+///     .expect("Cannot parse EPSG code(s) from the CRS-(E)VLR(s)")
+///     .expect("The Lidar file had no CRS");
+/// ```
+pub fn get_epsg_crs_from_reader<R: Read + Seek>(mut read: R) -> Result<Option<EpsgCRS>> {
+    let raw_header = las::raw::Header::read_from(&mut read)?;
+    read.seek(SeekFrom::Start(raw_header.header_size as u64))
+        .map_err(las::Error::from)?;
+
+    let number_of_vlrs = raw_header.number_of_variable_length_records;
+    let evlr = raw_header
+        .evlr
+        .as_ref()
+        .map(|evlr| (evlr.start_of_first_evlr, evlr.number_of_evlrs));
+
+    let mut builder = Builder::new(raw_header)?;
+    for _ in 0..number_of_vlrs {
+        match read_vlr(&mut read, false) {
+            Ok(VlrRead::Complete(vlr)) => builder.vlrs.push(vlr),
+            Ok(VlrRead::Truncated(vlr)) if is_crs_vlr(&vlr) => {
+                return Err(Error::TruncatedCrsVlr {
+                    record_id: vlr.record_id,
+                    data: vlr.data,
+                });
+            }
+            Ok(VlrRead::Truncated(_)) => {
+                log!(
+                    Level::Warn,
+                    "The VLR section is truncated, skipping the remaining VLRs"
+                );
+                break;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                log!(
+                    Level::Warn,
+                    "The VLR count overruns the data, skipping the remaining VLRs"
+                );
+                break;
+            }
+            Err(e) => return Err(las::Error::from(e).into()),
+        }
+    }
+
+    if let Some((start, number_of_evlrs)) = evlr
+        && number_of_evlrs > 0
+    {
+        match read.seek(SeekFrom::Start(start)) {
+            Err(e) => log!(Level::Warn, "Unable to read the EVLRs, skipping them: {e}"),
+            Ok(_) => {
+                for _ in 0..number_of_evlrs {
+                    match read_vlr(&mut read, true) {
+                        Ok(VlrRead::Complete(evlr)) => builder.evlrs.push(evlr),
+                        Ok(VlrRead::Truncated(evlr)) if is_crs_vlr(&evlr) => {
+                            return Err(Error::TruncatedCrsVlr {
+                                record_id: evlr.record_id,
+                                data: evlr.data,
+                            });
+                        }
+                        Ok(VlrRead::Truncated(_)) | Err(_) => {
+                            log!(
+                                Level::Warn,
+                                "Unable to read the EVLRs, skipping the remaining EVLRs"
+                            );
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    builder.into_header()?.get_epsg_crs()
+}
+
+/// Parse the EPSG code(s) from (E)VLRs read with the [las::raw] API, without building a [las::Header]
+///
+/// The `LASF_Projection` (E)VLRs are mapped to the extractors by record id:
+/// - 2112 (WKT-CRS) is parsed with [get_epsg_from_wkt_crs_bytes]
+/// - 34735 (GeoKeyDirectory) is read as little-endian u16s, 34736 (GeoDoubleParams) as little-endian f64s
+///   and 34737 (GeoAsciiParams) as is, and parsed with [get_epsg_from_geokey_directory]
+///
+/// As in [ParseEpsgCRS::get_epsg_crs] the WKT-CRS is preferred, but paired vertical WKT-CRS (E)VLRs are not
+/// looked for. Returns [Error::MalformedCrsVlr] if there are CRS (E)VLRs but neither of the above,
+/// and `Ok(None)` if there are no CRS (E)VLRs
+pub fn get_epsg_from_raw_vlrs(vlrs: &[las::raw::Vlr]) -> Result<Option<EpsgCRS>> {
+    let is_crs_user_id = |vlr: &las::raw::Vlr| vlr_string(&vlr.user_id) == CRS_VLR_USER_ID;
+    let payload = |record_id: u16| {
+        vlrs.iter()
+            .find(|vlr| is_crs_user_id(vlr) && vlr.record_id == record_id)
+            .map(|vlr| vlr.data.as_slice())
+    };
+
+    if let Some(wkt) = payload(2112) {
+        return get_epsg_from_wkt_crs_bytes(wkt).map(Some);
+    }
+    if let Some(directory) = payload(34735) {
+        let keys = geokey_directory_shorts(directory);
+        let doubles: Vec<f64> = payload(34736)
+            .unwrap_or_default()
+            .chunks_exact(8)
+            .map(|double| f64::from_le_bytes(double.try_into().expect("chunks are 8 bytes")))
+            .collect();
+        let ascii = payload(34737).unwrap_or_default();
+        return get_epsg_from_geokey_directory(&keys, &doubles, ascii).map(Some);
+    }
+    if vlrs
+        .iter()
+        .any(|vlr| is_crs_user_id(vlr) && CRS_VLR_RECORD_IDS.contains(&vlr.record_id))
+    {
+        return Err(Error::MalformedCrsVlr);
+    }
+    Ok(None)
+}
+
+/// A (E)VLR read by [read_vlr]
+enum VlrRead {
+    Complete(Vlr),
+    /// The reader ended within the payload, the data is the partial payload
+    Truncated(Vlr),
+}
+
+/// Read a VLR, or an EVLR if `extended`, keeping the partial payload if the reader ends within it
+fn read_vlr<R: Read>(read: &mut R, extended: bool) -> std::io::Result<VlrRead> {
+    let mut header = [0; 60];
+    let header = &mut header[..if extended { 60 } else { 54 }];
+    read.read_exact(header)?;
+
+    let (length, description) = if extended {
+        let mut length = [0; 8];
+        length.copy_from_slice(&header[20..28]);
+        (u64::from_le_bytes(length), &header[28..60])
+    } else {
+        (
+            u16::from_le_bytes([header[20], header[21]]) as u64,
+            &header[22..54],
+        )
+    };
+
+    let mut data = Vec::new();
+    read.take(length).read_to_end(&mut data)?;
+
+    let vlr = Vlr {
+        user_id: vlr_string(&header[2..18]),
+        record_id: u16::from_le_bytes([header[18], header[19]]),
+        description: vlr_string(description),
+        data,
+    };
+    if (vlr.data.len() as u64) < length {
+        Ok(VlrRead::Truncated(vlr))
+    } else {
+        Ok(VlrRead::Complete(vlr))
+    }
+}
+
+/// Read a nul-padded (E)VLR header string
+fn vlr_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+/// Tries to parse EPSG code(s) from WKT-CRS bytes.
+///
+/// By parsing the EPSG codes at the end of the vertical and horizontal CRS sub-strings.
+/// The `AUTHORITY["EPSG", ...]` or `ID["EPSG", ...]` node of the horizontal
+/// and vertical CRS nodes are used instead when present.
+/// This is not true WKT parser and might provide a bad code if
+/// the WKT-CRS bytes does not look as expected
+///
+/// Returns [Error::UnreadableWktCrs] if the bytes do not start with a WKT node, f.ex `PROJCS[`
+///
+/// With the `zstd` feature, zstd-compressed WKT-CRS bytes are decompressed before parsing
+/// and [Error::UnreadableWktCrs] is returned if decompression fails
+pub fn get_epsg_from_wkt_crs_bytes(bytes: &[u8]) -> Result<EpsgCRS> {
+    get_epsg_from_wkt_crs_bytes_with_diagnostics(bytes).0
+}
+
+/// The keywords the WKT-CRS is split on to find the vertical CRS node,
+/// `VERT_CS` for WKT v1 and `VERTCRS` or `VERTICALCRS` for v2
+pub const DEFAULT_VERTICAL_KEYWORDS: &[&str] = &["VERTCRS", "VERTICALCRS", "VERT_CS"];
+
+/// Same as [get_epsg_from_wkt_crs_bytes], but splits the WKT-CRS on the first of the given
+/// vertical CRS keywords found instead of [DEFAULT_VERTICAL_KEYWORDS]
+///
+/// Useful for WKT from writers using non-standard keywords for the vertical CRS node
+pub fn get_epsg_from_wkt_crs_bytes_with_vertical_keywords(
+    bytes: &[u8],
+    vertical_keywords: &[&str],
+) -> Result<EpsgCRS> {
+    parse_wkt_crs_bytes(bytes, vertical_keywords).0
+}
+
+/// Parse the EPSG code(s) from the `<SRS>` element of a GDAL PAM `.aux.xml` sidecar file,
+/// f.ex a `.las.aux.xml` written alongside a lidar file without CRS (E)VLRs
+///
+/// The WKT-CRS in the element is unescaped and parsed with [get_epsg_from_wkt_crs_bytes].
+/// Returns `Ok(None)` if the document has no `<SRS>` element
+pub fn get_epsg_from_gdal_aux_xml(xml_bytes: &[u8]) -> Result<Option<EpsgCRS>> {
+    let xml = decode_vlr_bytes(xml_bytes);
+    // the full tag name, so f.ex `<SRSName>` does not match
+    let Some(start) = xml
+        .match_indices("<SRS")
+        .map(|(i, tag)| i + tag.len())
+        .find(|&i| xml[i..].starts_with(|c: char| c == '>' || c.is_ascii_whitespace()))
+    else {
+        return Ok(None);
+    };
+    let element = &xml[start..];
+    // skip the attributes, f.ex dataAxisToSRSAxisMapping
+    let (_, content) = element.split_once('>').ok_or(Error::UnreadableWktCrs)?;
+    let (wkt, _) = content
+        .split_once("</SRS>")
+        .ok_or(Error::UnreadableWktCrs)?;
+    let wkt = wkt
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&");
+    get_epsg_from_wkt_crs_bytes(wkt.as_bytes()).map(Some)
+}
+
+/// Diagnostics from parsing WKT-CRS bytes, for auditing the parsed codes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WktParseDiagnostics {
+    /// Number of `AUTHORITY`/`ID` nodes with an EPSG code found in the WKT
+    pub epsg_authority_nodes: usize,
+    /// The keyword of the outermost horizontal CRS node, f.ex `GEOGCRS` or `PROJCS`, if any
+    pub horizontal_keyword: Option<String>,
+    /// The vertical CRS keyword the WKT was split on, if any
+    pub vertical_keyword: Option<String>,
+    /// The horizontal code as parsed, before checking against [EPSG_RANGE]
+    pub parsed_horizontal: u16,
+    /// The vertical code as parsed, before checking against [EPSG_RANGE]
+    pub parsed_vertical: Option<u16>,
+    /// The vertical code was read from the authority node of the vertical CRS node,
+    /// rather than from the trailing digits of the vertical sub-string
+    pub vertical_from_authority: bool,
+    /// A vertical code was parsed, but set to `None` as it was outside of [EPSG_RANGE]
+    pub vertical_defaulted_to_none: bool,
+    /// A code was found by the trailing-digit scan without a matching `AUTHORITY`/`ID` node,
+    /// so it should be trusted less than an authority-derived code
+    pub used_fallback_heuristic: bool,
+}
+
+/// Same as [get_epsg_from_wkt_crs_bytes], but also returns [WktParseDiagnostics]
+/// describing how the codes were found, also when parsing fails
+pub fn get_epsg_from_wkt_crs_bytes_with_diagnostics(
+    bytes: &[u8],
+) -> (Result<EpsgCRS>, WktParseDiagnostics) {
+    parse_wkt_crs_bytes(bytes, DEFAULT_VERTICAL_KEYWORDS)
+}
+
+/// Parse the WKT-CRS bytes, splitting on the first of the `vertical_keywords` found
+fn parse_wkt_crs_bytes(
+    bytes: &[u8],
+    vertical_keywords: &[&str],
+) -> (Result<EpsgCRS>, WktParseDiagnostics) {
+    let bytes = match decompress_crs_payload(bytes) {
+        Ok(bytes) => bytes,
+        Err(e) => return (Err(e), WktParseDiagnostics::default()),
+    };
+    let wkt = decode_vlr_bytes(&bytes);
+    let wkt = normalize_wkt_whitespace(&wkt);
+    if !starts_with_wkt_node(&wkt) {
+        return (Err(Error::UnreadableWktCrs), WktParseDiagnostics::default());
+    }
+    let mut diagnostics = WktParseDiagnostics {
+        epsg_authority_nodes: wkt_authority_nodes(wkt.as_bytes()).len(),
+        ..Default::default()
+    };
+    // splitting on the keywords of one dialect could give the codes of the wrong nodes
+    if has_mixed_wkt_dialects(&wkt) {
+        return (Err(Error::MixedWktDialects), diagnostics);
+    }
+
+    // the members of a compound CRS are classified by keyword, so f.ex a trailing TIMECRS
+    // member is not mistaken for a part of the vertical CRS
+    let members = wkt_compound_members(&wkt).unwrap_or_default();
+    let horizontal_member = members
+        .iter()
+        .find(|member| HORIZONTAL_CRS_KEYWORDS.contains(&wkt_node_keyword(member)));
+    let vertical_member = members.iter().find_map(|member| {
+        let keyword = wkt_node_keyword(member);
+        vertical_keywords
+            .iter()
+            .find(|&&k| k == keyword)
+            .map(|&k| (k, &member[k.len()..]))
+    });
+    let skipped: Vec<&str> = members
+        .iter()
+        .map(|member| wkt_node_keyword(member))
+        .filter(|keyword| {
+            !HORIZONTAL_CRS_KEYWORDS.contains(keyword) && !vertical_keywords.contains(keyword)
+        })
+        .collect();
+    if !skipped.is_empty() {
+        log!(
+            Level::Debug,
+            "Skipping the compound CRS members {skipped:?}"
+        );
+    }
+
+    let split = match (horizontal_member, vertical_member) {
+        (Some(horizontal), Some((keyword, vertical))) => Some((keyword, (*horizontal, vertical))),
+        _ => vertical_keywords.iter().find_map(|&keyword| {
+            split_at_wkt_keyword(&wkt, keyword).map(|pieces| (keyword, pieces))
+        }),
+    };
+
+    let (horizontal_wkt, vertical) = match split {
+        Some((keyword, (horizontal, vertical))) => {
+            diagnostics.vertical_keyword = Some(keyword.to_string());
+            // prefer the vertical node's own authority, as trailing
+            // nodes (f.ex AXIS) would break the trailing-digit scan
+            let vertical = match wkt_node_authority(vertical.as_bytes()) {
+                Some(code) => {
+                    diagnostics.vertical_from_authority = true;
+                    code
+                }
+                None => trailing_epsg_code(vertical.as_bytes()),
+            };
+            (horizontal, Some(vertical))
+        }
+        None => (horizontal_member.copied().unwrap_or(&wkt), None),
+    };
+    // prefer the horizontal node's own authority, as trailing
+    // nodes (f.ex the BBOX of a WKT2-2019 USAGE) would break the trailing-digit scan
+    diagnostics.horizontal_keyword =
+        outermost_horizontal_node(horizontal_wkt).map(|(keyword, _)| keyword.to_string());
+    let (horizontal, horizontal_from_authority) = match horizontal_node_authority(horizontal_wkt) {
+        Some(code) => (code, true),
+        None => {
+            let code = trailing_epsg_code(horizontal_wkt.as_bytes());
+            // the code is authoritative if it is the last authority node of the horizontal sub-string
+            let from_authority = wkt_authority_nodes(horizontal_wkt.as_bytes())
+                .last()
+                .is_some_and(|&(_, c)| c == code);
+            (code, from_authority)
+        }
+    };
+    diagnostics.used_fallback_heuristic =
+        !horizontal_from_authority || (vertical.is_some() && !diagnostics.vertical_from_authority);
+    diagnostics.parsed_horizontal = horizontal;
+    diagnostics.parsed_vertical = vertical;
+
+    let mut code = EpsgCRS {
+        horizontal,
+        vertical,
+    };
+
+    if !EPSG_RANGE.contains(&code.horizontal) {
+        return (Err(Error::BadHorizontalCodeParsed(code)), diagnostics);
+    }
+    if let Some(v_code) = code.vertical
+        && !EPSG_RANGE.contains(&v_code)
+    {
+        code.vertical = None;
+        diagnostics.vertical_defaulted_to_none = true;
+    }
+    (Ok(code), diagnostics)
+}
+
+/// The keywords of horizontal CRS nodes, for WKT v1 and v2
+const HORIZONTAL_CRS_KEYWORDS: &[&str] = &[
+    "PROJCS",
+    "GEOGCS",
+    "GEOCCS",
+    "PROJCRS",
+    "PROJECTEDCRS",
+    "GEOGCRS",
+    "GEOGRAPHICCRS",
+    "GEODCRS",
+    "GEODETICCRS",
+];
+
+/// Find the outermost horizontal CRS node of the WKT, returns its keyword and the node after the keyword
+fn outermost_horizontal_node(wkt: &str) -> Option<(&'static str, &str)> {
+    HORIZONTAL_CRS_KEYWORDS
+        .iter()
+        .filter_map(|&keyword| split_at_wkt_keyword(wkt, keyword).map(|pieces| (keyword, pieces)))
+        .min_by_key(|(_, (before, _))| before.len())
+        .map(|(keyword, (_, node))| (keyword, node))
+}
+
+/// Find the EPSG code in the authority node of the outermost horizontal CRS node of the WKT
+///
+/// Only the node's own authority is used, so f.ex the authority of a `DATUM` sub-node
+/// of a geographic root node is never returned instead of the CRS code
+fn horizontal_node_authority(wkt: &str) -> Option<u16> {
+    let (_, node) = outermost_horizontal_node(wkt)?;
+    wkt_node_authority(node.as_bytes())
+}
+
+/// Split the WKT around the first node with the keyword
+///
+/// Only whole keywords opening a node match, so f.ex `VERT` does not match `VERT_DATUM[`
+/// and `VERT_CS` does not match `VERT_CSX[`
+fn split_at_wkt_keyword<'a>(wkt: &'a str, keyword: &str) -> Option<(&'a str, &'a str)> {
+    wkt.match_indices(keyword).find_map(|(start, _)| {
+        let end = start + keyword.len();
+        let preceded_by_ident = wkt[..start]
+            .bytes()
+            .next_back()
+            .is_some_and(|b| b.is_ascii_alphanumeric() || b == b'_');
+        let opens_node = matches!(wkt.as_bytes().get(end), Some(b'[' | b'('));
+        (!preceded_by_ident && opens_node).then(|| (&wkt[..start], &wkt[end..]))
+    })
+}
+
+/// The keywords of compound CRS nodes, for WKT v1 and v2
+const COMPOUND_CRS_KEYWORDS: &[&str] = &["COMPD_CS", "COMPOUNDCRS"];
+
+/// Split a compound CRS node into its member CRS nodes, f.ex `[GEOGCRS[..], VERTCRS[..], TIMECRS[..]]`
+///
+/// Returns `None` if the WKT is not a compound CRS node. A truncated last member is included as is
+fn wkt_compound_members(wkt: &str) -> Option<Vec<&str>> {
+    let keyword = wkt_node_keyword(wkt);
+    if !COMPOUND_CRS_KEYWORDS.contains(&keyword) {
+        return None;
+    }
+    let body = wkt.get(keyword.len() + 1..)?;
+
+    let mut members = Vec::new();
+    let mut depth = 0_usize;
+    let mut in_string = false;
+    let mut member_start = 0;
+    for (i, byte) in body.bytes().enumerate() {
+        if in_string {
+            in_string = byte != b'"';
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'[' | b'(' => depth += 1,
+            b']' | b')' if depth == 0 => {
+                // the compound node is closed
+                members.push(&body[member_start..i]);
+                member_start = body.len();
+                break;
+            }
+            b']' | b')' => depth -= 1,
+            b',' if depth == 0 => {
+                members.push(&body[member_start..i]);
+                member_start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    if member_start < body.len() {
+        members.push(&body[member_start..]);
+    }
+    // the name and any trailing ID/AUTHORITY are not CRS nodes
+    members.retain(|member| {
+        starts_with_wkt_node(member) && !matches!(wkt_node_keyword(member), "ID" | "AUTHORITY")
+    });
+    Some(members)
+}
+
+/// The keyword of the node the WKT starts with, f.ex `PROJCS` for `PROJCS["..",..]`
+fn wkt_node_keyword(wkt: &str) -> &str {
+    let len = wkt
+        .bytes()
+        .take_while(|b| b.is_ascii_alphanumeric() || *b == b'_')
+        .count();
+    &wkt[..len]
+}
+
+/// Whether the WKT starts with a keyword opening a node, f.ex `PROJCS[`,
+/// to tell (possibly truncated) WKT apart from bytes that are not WKT at all
+fn starts_with_wkt_node(wkt: &str) -> bool {
+    let wkt = wkt.trim_start();
+    let keyword_len = wkt
+        .bytes()
+        .take_while(|b| b.is_ascii_alphanumeric() || *b == b'_')
+        .count();
+    keyword_len > 0 && matches!(wkt.as_bytes().get(keyword_len), Some(b'[' | b'('))
+}
+
+/// Remove whitespace outside of quoted strings in WKT
+///
+/// Whitespace is insignificant between WKT tokens, but pretty-printing writers
+/// use newlines and tabs between nodes which could break keyword matching
+fn normalize_wkt_whitespace(wkt: &str) -> Cow<'_, str> {
+    let mut in_string = false;
+    let has_interior_whitespace = wkt.trim().bytes().any(|b| {
+        if b == b'"' {
+            in_string = !in_string;
+        }
+        !in_string && b.is_ascii_whitespace()
+    });
+    if !has_interior_whitespace {
+        return Cow::Borrowed(wkt);
+    }
+
+    let mut in_string = false;
+    Cow::Owned(
+        wkt.chars()
+            .filter(|&c| {
+                if c == '"' {
+                    in_string = !in_string;
+                }
+                in_string || !c.is_ascii_whitespace()
+            })
+            .collect(),
+    )
+}
+
+/// Parse the EPSG code from the digits at the end of a WKT sub-string
+///
+/// Returns 0 if no code is found
+fn trailing_epsg_code(bytes: &[u8]) -> u16 {
+    // the EPSG code is located at the end of the substrings
+    // and so we iterate through the substrings backwards collecting
+    // digits and adding them to our EPSG code
+    let mut epsg_code: u32 = 0;
+    let mut code_has_started = false;
+    let mut power: u32 = 1;
+    // the 10 last bytes should be enough (with a small margin)
+    // as the code is 4 or 5 digits starting at the 2nd or 3rd byte from the back
+    for byte in bytes.trim_ascii_end().iter().rev().take(10) {
+        // if the byte is an ASCII encoded digit
+        if byte.is_ascii_digit() {
+            // mark that the EPSG code has started
+            // so that we can break when we no
+            // longer find digits
+            code_has_started = true;
+
+            // translate from ASCII to digits
+            // and multiply by powers of 10
+            // sum it to build the EPSG
+            // code digit by digit
+            // saturate so that long digit runs don't overflow,
+            // they end up outside of EPSG_RANGE anyway
+            let digit = (byte - b'0') as u32;
+            epsg_code = epsg_code.saturating_add(power.saturating_mul(digit));
+            power = power.saturating_mul(10);
+        } else if code_has_started {
+            // we no longer see digits
+            // so the code must be over
+            break;
+        }
+    }
+    u16::try_from(epsg_code).unwrap_or(0)
+}
+
+/// List every EPSG authority node in WKT-CRS bytes together with the keyword of the node it belongs to
+///
+/// F.ex `PROJCS["...",...,AUTHORITY["EPSG","2992"]]` gives `("PROJCS", 2992)`.
+/// The pairs are returned in the order the authority nodes appear in the WKT,
+/// which explains which structural element each code belongs to.
+/// Both `AUTHORITY` (WKT1) and `ID` (WKT2) nodes are recognized.
+pub fn wkt_authority_nodes(bytes: &[u8]) -> Vec<(String, u16)> {
+    let Ok(tokens) = tokenize_wkt(bytes) else {
+        return Vec::new();
+    };
+
+    let mut nodes = Vec::new();
+    let mut keywords: Vec<&str> = Vec::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        match token.kind {
+            WktTokenKind::OpenBracket => {
+                let keyword = match i.checked_sub(1).map(|j| &tokens[j].kind) {
+                    Some(WktTokenKind::Keyword(keyword)) => keyword.as_str(),
+                    _ => "",
+                };
+                if (keyword == "AUTHORITY" || keyword == "ID")
+                    && let Some(parent) = keywords.last()
+                    && let Some(code) = authority_tokens_code(&tokens[i + 1..])
+                {
+                    nodes.push((parent.to_string(), code));
+                }
+                keywords.push(keyword);
+            }
+            WktTokenKind::CloseBracket => {
+                keywords.pop();
+            }
+            _ => (),
+        }
+    }
+    nodes
+}
+
+/// Parse the code from the tokens inside an authority node, f.ex `"EPSG","5703"]`
+fn authority_tokens_code(tokens: &[WktToken]) -> Option<u16> {
+    match tokens {
+        [name, code, ..] => match (&name.kind, &code.kind) {
+            (
+                WktTokenKind::String(name),
+                WktTokenKind::String(code) | WktTokenKind::Number(code),
+            ) if name.eq_ignore_ascii_case("EPSG") => code.trim().parse().ok(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The kind of a [WktToken]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WktTokenKind {
+    /// A keyword or an unquoted enumeration value, f.ex `PROJCS` or `EAST`
+    Keyword(String),
+    /// `[` or `(`
+    OpenBracket,
+    /// `]` or `)`
+    CloseBracket,
+    /// The contents of a quoted string, with doubled quotes unescaped
+    String(String),
+    /// A number as written, f.ex `6378137` or `298.257223563`
+    Number(String),
+    /// An unquoted ISO 8601 datetime as written, f.ex `1980-01-06T00:00:00.0Z` in WKT2 `TIMEORIGIN` nodes
+    DateTime(String),
+}
+
+/// A token of WKT-CRS, with the byte span it was read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WktToken {
+    pub kind: WktTokenKind,
+    /// The byte range of the token in the tokenized bytes, including the quotes of strings
+    pub span: Range<usize>,
+}
+
+/// Split WKT-CRS bytes into [WktToken]s, for custom extraction of f.ex units and datums
+///
+/// Commas and whitespace separate tokens and are not returned.
+/// Tokenizing stops at the first nul byte, as some writers nul-terminate the WKT-CRS (E)VLR.
+/// The brackets are not checked for balance.
+///
+/// Returns [Error::UnreadableWktCrs] on an unterminated string or an unexpected character
+pub fn tokenize_wkt(bytes: &[u8]) -> Result<Vec<WktToken>> {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    let bytes = &bytes[..end];
+
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let kind = match bytes[i] {
+            b',' => {
+                i += 1;
+                continue;
+            }
+            b if b.is_ascii_whitespace() => {
+                i += 1;
+                continue;
+            }
+            b'[' | b'(' => {
+                i += 1;
+                WktTokenKind::OpenBracket
+            }
+            b']' | b')' => {
+                i += 1;
+                WktTokenKind::CloseBracket
+            }
+            b'"' => {
+                let mut value = Vec::new();
+                i += 1;
+                loop {
+                    match bytes.get(i) {
+                        None => return Err(Error::UnreadableWktCrs),
+                        // a doubled quote is an escaped quote
+                        Some(b'"') if bytes.get(i + 1) == Some(&b'"') => {
+                            value.push(b'"');
+                            i += 2;
+                        }
+                        Some(b'"') => {
+                            i += 1;
+                            break;
+                        }
+                        Some(&b) => {
+                            value.push(b);
+                            i += 1;
+                        }
+                    }
+                }
+                WktTokenKind::String(decode_vlr_bytes(&value).into_owned())
+            }
+            b if b.is_ascii_alphabetic() || b == b'_' => {
+                i += bytes[i..]
+                    .iter()
+                    .take_while(|b| b.is_ascii_alphanumeric() || **b == b'_')
+                    .count();
+                WktTokenKind::Keyword(String::from_utf8_lossy(&bytes[start..i]).into_owned())
+            }
+            b if b.is_ascii_digit() || matches!(b, b'-' | b'+' | b'.') => {
+                i += bytes[i..]
+                    .iter()
+                    .take_while(|b| {
+                        b.is_ascii_digit() || matches!(b, b'-' | b'+' | b'.' | b'e' | b'E')
+                    })
+                    .count();
+                // ISO 19162 allows unquoted datetimes, f.ex `TIMEORIGIN[1980-01-06T00:00:00.0Z]`
+                if bytes
+                    .get(i)
+                    .is_some_and(|b| matches!(b, b'T' | b':' | b'Z'))
+                {
+                    i += bytes[i..]
+                        .iter()
+                        .take_while(|b| {
+                            b.is_ascii_digit()
+                                || matches!(b, b'-' | b'+' | b'.' | b':' | b'T' | b'Z')
+                        })
+                        .count();
+                    WktTokenKind::DateTime(String::from_utf8_lossy(&bytes[start..i]).into_owned())
+                } else {
+                    WktTokenKind::Number(String::from_utf8_lossy(&bytes[start..i]).into_owned())
+                }
+            }
+            _ => return Err(Error::UnreadableWktCrs),
+        };
+        tokens.push(WktToken {
+            kind,
+            span: start..i,
+        });
+    }
+    Ok(tokens)
+}
+
+/// Metadata read from WKT-CRS alongside the EPSG code(s), see [get_wkt_metadata]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WktMetadata {
+    /// The name of the horizontal datum, f.ex `"NAD83_High_Accuracy_Reference_Network"`
+    ///
+    /// Useful for telling f.ex NAD83 and NAD83(HARN) apart when the codes are missing or unreliable
+    pub datum_name: Option<String>,
+}
+
+/// Read [WktMetadata] from WKT-CRS bytes
+///
+/// The datum name is the name of the first `DATUM` (WKT1 and v2) or `GEODETICDATUM` (WKT2) node,
+/// vertical datum nodes (f.ex `VERT_DATUM` and `VDATUM`) are not considered.
+///
+/// Returns [Error::UnreadableWktCrs] if the bytes cannot be tokenized, see [tokenize_wkt]
+pub fn get_wkt_metadata(bytes: &[u8]) -> Result<WktMetadata> {
+    let bytes = decompress_crs_payload(bytes)?;
+    let tokens = tokenize_wkt(&bytes)?;
+    let datum_name = tokens.windows(3).find_map(|window| match window {
+        [
+            WktToken {
+                kind: WktTokenKind::Keyword(keyword),
+                ..
+            },
+            WktToken {
+                kind: WktTokenKind::OpenBracket,
+                ..
+            },
+            WktToken {
+                kind: WktTokenKind::String(name),
+                ..
+            },
+        ] if keyword == "DATUM" || keyword == "GEODETICDATUM" => Some(name.clone()),
+        _ => None,
+    });
+    Ok(WktMetadata { datum_name })
+}
+
+/// Find the EPSG code in the authority node belonging to a WKT node
+///
+/// `node` should start at the opening bracket of the node, f.ex the bytes after `VERT_CS`.
+/// Only direct children of the node are considered, so the authorities of sub-nodes
+/// like `VERT_DATUM` and `UNIT` are skipped. Both `AUTHORITY["EPSG","5703"]` (WKT1)
+/// and `ID["EPSG",5703]` (WKT2) are recognized.
+fn wkt_node_authority(node: &[u8]) -> Option<u16> {
+    let mut depth = 0_usize;
+    let mut in_string = false;
+    let mut keyword_start = None;
+    let mut code = None;
+
+    for (i, &byte) in node.iter().enumerate() {
+        if in_string {
+            // a doubled quote inside a string toggles us out and back in again
+            in_string = byte != b'"';
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'[' | b'(' => {
+                depth += 1;
+                if depth == 2
+                    && let Some(start) = keyword_start
+                {
+                    let keyword = node[start..i].trim_ascii();
+                    if (keyword == b"AUTHORITY" || keyword == b"ID")
+                        && let Some(c) = parse_wkt_authority_body(&node[i + 1..])
+                    {
+                        code = Some(c);
+                    }
+                }
+                keyword_start = None;
+            }
+            b']' | b')' => {
+                if depth <= 1 {
+                    // the node is closed
+                    break;
+                }
+                depth -= 1;
+            }
+            b',' if depth == 1 => keyword_start = Some(i + 1),
+            _ => (),
+        }
+    }
+    code
+}
+
+/// Parse the code from the inside of an authority node, f.ex `"EPSG","5703"]`
+///
+/// The authority name is matched case-insensitively, as some writers lowercase it
+fn parse_wkt_authority_body(body: &[u8]) -> Option<u16> {
+    let end = body.iter().position(|&b| b == b']' || b == b')')?;
+    let mut fields = body[..end].split(|&b| b == b',');
+
+    if !fields
+        .next()?
+        .trim_ascii()
+        .eq_ignore_ascii_case(b"\"EPSG\"")
+    {
+        return None;
+    }
+    let code = fields.next()?.trim_ascii();
+    let code = code.strip_prefix(b"\"").unwrap_or(code);
+    let code = code.strip_suffix(b"\"").unwrap_or(code);
+    std::str::from_utf8(code).ok()?.trim().parse().ok()
+}
+
+/// Get the EPSG code(s) from GeoTiff-CRS-data
+/// Only handles geotiff u16 data
+/// Returns ascii and double defined crs data in an [Error::UnimplementedForGeoTiffStringAndDoubleData]
+///
+/// If no horizontal code is found in the u16 keys, the citation keys
+/// (1026, 2049 and 3073) are scanned for `EPSG:<code>` tokens.
+/// The most specific citation holding a token is used, the projected citation (3073) before
+/// the geographic (2049) and the general one (1026).
+/// Its first token is used as the horizontal code and the second as the vertical code
+///
+/// A code key (2048 or 3072) referencing the ascii params instead of holding the code inline
+/// is resolved from its `EPSG:<code>` token or bare code, if neither is found (and no citation
+/// gives the code) its data is returned in an [Error::UnimplementedForGeoTiffStringAndDoubleData]
+pub fn get_epsg_from_geotiff_crs(geotiff_crs_data: &GeoTiffCrs) -> Result<EpsgCRS> {
+    if log_enabled!(Level::Debug) {
+        let skipped: Vec<u16> = geotiff_crs_data
+            .entries
+            .iter()
+            .map(|entry| entry.id)
+            .filter(|id| !RECOGNIZED_GEOTIFF_KEYS.contains(id))
+            .collect();
+        log!(
+            Level::Debug,
+            "GeoTiff-CRS with {} key entries, skipping keys {skipped:?}",
+            geotiff_crs_data.entries.len()
+        );
+    }
+
+    let mut out = (0, None);
+    let mut citations = Vec::new();
+    let mut unresolved_code_key = None;
+    for entry in geotiff_crs_data.entries.iter() {
+        match entry.id {
+            // 2048 and 3072 should not co-exist, but might both be combined with 4096
+            // 1024 should always exist
+            1024 => match &entry.data {
+                GeoTiffData::U16(0) => (), // should really not be zero, but let's rather error out later just in case
+                GeoTiffData::U16(1) => (), // projected crs
+                GeoTiffData::U16(2) => (), // geographic crs
+                GeoTiffData::U16(3) => (), // geographic + a vertical crs
+                GeoTiffData::U16(USER_DEFINED_CODE) => return Err(Error::UserDefinedCrs),
+                _ => {
+                    return Err(Error::UnimplementedForGeoTiffStringAndDoubleData(
+                        entry.data.clone(),
+                    ));
+                }
+            },
+            2048 | 3072 => match &entry.data {
+                GeoTiffData::U16(v) => out.0 = *v,
+                // the key references the ascii params (34737) instead of holding the code inline
+                GeoTiffData::String(s) => match resolve_ascii_code(s) {
+                    Some(v) => out.0 = v,
+                    None => unresolved_code_key = Some(entry.data.clone()),
+                },
+                GeoTiffData::Doubles(_) => unresolved_code_key = Some(entry.data.clone()),
+            },
+            4096 => {
+                // vertical crs
+                if let GeoTiffData::U16(v) = entry.data {
+                    out.1 = Some(v);
+                }
+            }
+            1026 | 2049 | 3073 => {
+                // citations, only used if the code keys are missing
+                if let GeoTiffData::String(s) = &entry.data {
+                    citations.push((entry.id, s.as_str()));
+                }
+            }
+            _ => (), // the rest are descriptions and units.
+        }
+    }
+
+    if out.0 == 0 {
+        // the most specific citation wins: PCSCitation (3073), then GeogCitation (2049), then GTCitation (1026)
+        citations.sort_by_key(|(id, _)| std::cmp::Reverse(*id));
+        for (_, citation) in citations {
+            let (horizontal, vertical) = scan_citation_for_epsg(citation);
+            if let Some(h) = horizontal {
+                out.0 = h;
+                if out.1.is_none() {
+                    out.1 = vertical;
+                }
+                break;
+            }
+        }
+    }
+
+    if out.0 == 0
+        && let Some(data) = unresolved_code_key
+    {
+        return Err(Error::UnimplementedForGeoTiffStringAndDoubleData(data));
+    }
+    if out.0 == 0 {
+        Err(las::Error::UnreadableGeoTiffCrs)?
+    }
+
+    let mut code = EpsgCRS {
+        horizontal: out.0,
+        vertical: out.1,
+    };
+
+    if !EPSG_RANGE.contains(&code.horizontal) {
+        return Err(Error::BadHorizontalCodeParsed(code));
+    }
+    if let Some(v_code) = code.vertical
+        && !EPSG_RANGE.contains(&v_code)
+    {
+        code.vertical = None;
+    }
+    Ok(code)
+}
+
+/// Get the EPSG code(s) from the raw payloads of the GeoTiff-CRS (E)VLRs, for tools
+/// that do not use the [las::crs::GeoTiffCrs] representation
+///
+/// `keys` is the GeoKeyDirectory (record id 34735), a header of 4 shorts (version, revision,
+/// minor revision and number of keys) followed by 4 shorts per key (id, tag location, count and value/offset).
+/// Keys with tag location 34736 are read from `doubles` (record id 34736) and keys with tag location 34737
+/// from `ascii` (record id 34737). The keys are then parsed as in [get_epsg_from_geotiff_crs].
+///
+/// Returns [las::Error::UnreadableGeoTiffCrs] if the directory is truncated or a key points outside of its payload
+pub fn get_epsg_from_geokey_directory(
+    keys: &[u16],
+    doubles: &[f64],
+    ascii: &[u8],
+) -> Result<EpsgCRS> {
+    check_geokey_directory_version(keys);
+    let number_of_keys = *keys.get(3).ok_or(las::Error::UnreadableGeoTiffCrs)? as usize;
+    let entries = keys
+        .get(4..4 + 4 * number_of_keys)
+        .ok_or(las::Error::UnreadableGeoTiffCrs)?;
+
+    let mut geotiff = GeoTiffCrs {
+        entries: Vec::with_capacity(number_of_keys),
+    };
+    for entry in entries.chunks_exact(4) {
+        let (id, location, count, value) = (entry[0], entry[1], entry[2], entry[3]);
+        let range = value as usize..value as usize + count as usize;
+        let data = match location {
+            0 => GeoTiffData::U16(value),
+            34736 => GeoTiffData::Doubles(
+                doubles
+                    .get(range)
+                    .ok_or(las::Error::UnreadableGeoTiffCrs)?
+                    .to_vec(),
+            ),
+            34737 => {
+                let ascii = ascii.get(range).ok_or(las::Error::UnreadableGeoTiffCrs)?;
+                // the strings are terminated by '|' in the ascii params
+                let ascii = ascii.strip_suffix(b"|").unwrap_or(ascii);
+                GeoTiffData::String(decode_vlr_bytes(ascii).into_owned())
+            }
+            _ => Err(las::Error::UnreadableGeoTiffCrs)?,
+        };
+        geotiff.entries.push(GeoTiffKeyEntry { id, data });
+    }
+    get_epsg_from_geotiff_crs(&geotiff)
+}
+
+/// Read the payload of a GeoKeyDirectory (E)VLR as the little-endian u16s it consists of
+fn geokey_directory_shorts(data: &[u8]) -> Vec<u16> {
+    data.chunks_exact(2)
+        .map(|short| u16::from_le_bytes([short[0], short[1]]))
+        .collect()
+}
+
+/// Warn if the header of a GeoKeyDirectory (key directory version, key revision and minor revision)
+/// is not GeoTIFF 1.0 (`1, 1, 0`) or 1.1 (`1, 1, 1`)
+///
+/// GeoTIFF 1.1 renamed some keys, but the ids of the keys read here (1024, 2048, 3072 and 4096)
+/// are unchanged, so both revisions are parsed the same way
+fn check_geokey_directory_version(keys: &[u16]) {
+    match keys {
+        [1, 1, 0, ..] => (),
+        [1, 1, 1, ..] => log!(
+            Level::Debug,
+            "GeoTIFF 1.1 key directory, parsed with the unchanged 1.0 key ids"
+        ),
+        [version, revision, minor, ..] => log!(
+            Level::Warn,
+            "Unexpected GeoTIFF key directory header {version}, {revision}, {minor}, expected 1, 1, 0 or 1, 1, 1. The key ids might differ"
+        ),
+        _ => (),
+    }
+}
+
+/// Same as [get_epsg_from_geotiff_crs], but returns [Error::GeoTiffKeyModelMismatch] if the
+/// code key does not match the model type (key 1024)
+///
+/// A projected model type (1) should have its code in key 3072 and a geographic model type (2 or 3)
+/// in key 2048. [get_epsg_from_geotiff_crs] reads either key regardless of the model type
+pub fn get_epsg_from_geotiff_crs_strict(geotiff_crs_data: &GeoTiffCrs) -> Result<EpsgCRS> {
+    let u16_key = |id: u16| {
+        geotiff_crs_data
+            .entries
+            .iter()
+            .find_map(|entry| match (entry.id, &entry.data) {
+                (key, GeoTiffData::U16(v)) if key == id => Some(*v),
+                _ => None,
+            })
+    };
+    let (geographic, projected) = (u16_key(2048), u16_key(3072));
+    match u16_key(1024) {
+        Some(model_type @ 1) if geographic.is_some() && projected.is_none() => {
+            return Err(Error::GeoTiffKeyModelMismatch {
+                model_type,
+                key: 2048,
+            });
+        }
+        Some(model_type @ (2 | 3)) if projected.is_some() => {
+            return Err(Error::GeoTiffKeyModelMismatch {
+                model_type,
+                key: 3072,
+            });
+        }
+        _ => (),
+    }
+    get_epsg_from_geotiff_crs(geotiff_crs_data)
+}
+
+/// Resolve the code of a code key (2048 or 3072) referencing the ascii params,
+/// either an `EPSG:<code>` token or a bare code like `"25832|"`
+fn resolve_ascii_code(ascii: &str) -> Option<u16> {
+    scan_citation_for_epsg(ascii)
+        .0
+        .or_else(|| ascii.trim_end_matches(['|', '\0']).trim().parse().ok())
+}
+
+/// Scan a GeoTiff citation string for up to two EPSG tokens, see [parse_epsg_token]
+///
+/// The first token is returned as the horizontal code and the second as the vertical code,
+/// as in `"ETRS89 / UTM zone 32N EPSG:25832 + NN2000 height EPSG:5941"`
+fn scan_citation_for_epsg(citation: &str) -> (Option<u16>, Option<u16>) {
+    let mut codes = citation
+        .match_indices("EPSG:")
+        .filter_map(|(start, _)| parse_epsg_token(&citation[start..]));
+    (codes.next(), codes.next())
+}
+
+/// Parse the code of an EPSG token at the start of the string, in any of the forms
+/// `EPSG:25832`, `EPSG::25832` or `EPSG:<version>:25832` (f.ex `EPSG:9.8.1:25832`)
+///
+/// Anything after the code is ignored. Returns `None` if the string does not start with an EPSG token
+fn parse_epsg_token(token: &str) -> Option<u16> {
+    let rest = token.strip_prefix("EPSG:")?;
+    let leading_digits = |s: &str| s.bytes().take_while(|b| b.is_ascii_digit()).count();
+
+    let version_len = rest
+        .bytes()
+        .take_while(|b| b.is_ascii_digit() || *b == b'.')
+        .count();
+    let code = match rest[version_len..].strip_prefix(':') {
+        // `EPSG::<code>` has an empty version
+        Some(code) => code,
+        None => rest,
+    };
+    code[..leading_digits(code)].parse().ok()
+}
+
+/// Get the EPSG code of the linear unit of the projected CRS, key 3076 (`ProjLinearUnitsGeoKey`)
+///
+/// F.ex 9001 for metres, 9002 for international feet and 9003 for US survey feet.
+/// Useful for warning about imperial-unit lidar files that need coordinate scaling
+pub fn get_geotiff_linear_unit(geotiff_crs: &GeoTiffCrs) -> Option<u16> {
+    geotiff_crs
+        .entries
+        .iter()
+        .find_map(|entry| match (entry.id, &entry.data) {
+            (3076, GeoTiffData::U16(v)) => Some(*v),
+            _ => None,
+        })
+}
+
+/// GeoTiff keys not affecting the EPSG code(s), which can help diagnose
+/// height-related CRS issues (f.ex ellipsoidal vs orthometric heights)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GeoTiffExtras {
+    /// Key 1025 (`GTRasterTypeGeoKey`), 1 for pixel-is-area and 2 for pixel-is-point
+    pub raster_type: Option<u16>,
+    /// Key 1026 (`GTCitationGeoKey`), often describing what the CRS applies to
+    pub citation: Option<String>,
+}
+
+/// Same as [get_epsg_from_geotiff_crs], but also returns the [GeoTiffExtras] of the GeoTiff-CRS
+pub fn get_epsg_from_geotiff_crs_with_extras(
+    geotiff_crs_data: &GeoTiffCrs,
+) -> Result<(EpsgCRS, GeoTiffExtras)> {
+    let code = get_epsg_from_geotiff_crs(geotiff_crs_data)?;
+    let mut extras = GeoTiffExtras::default();
+    for entry in geotiff_crs_data.entries.iter() {
+        match (entry.id, &entry.data) {
+            (1025, GeoTiffData::U16(v)) => extras.raster_type = Some(*v),
+            (1026, GeoTiffData::String(s)) => extras.citation = Some(s.clone()),
+            _ => (),
+        }
+    }
+    Ok((code, extras))
+}
+
+/// Functions that never return an error, for applications where failing to find the CRS is not fatal
+///
+/// Every strategy of the crate is tried and `None` is returned if none of them gives a CRS,
+/// with the failures summarized in a debug log
+pub mod best_effort {
+    use super::*;
+
+    /// Get the best available CRS of the header
+    ///
+    /// Tries [get_epsg_crs_with_fallback] ([ParseEpsgCRS::get_epsg_crs], then the GeoTiff-CRS
+    /// if the WKT-CRS is broken) and then the most confident result of [parse_all] (f.ex GeoTiff citations)
+    pub fn get_epsg(header: &Header) -> Option<EpsgCRS> {
+        let failure = match get_epsg_crs_with_fallback(header) {
+            Ok(crs) => return crs,
+            Err(e) => e,
+        };
+        if let Some((crs, source, confidence)) = parse_all(header).into_iter().next() {
+            log!(
+                Level::Debug,
+                "Using the CRS from {source:?} with confidence {confidence}, after failure: {failure}"
+            );
+            return Some(crs);
+        }
+        log!(Level::Debug, "No CRS found: {failure}");
+        None
+    }
+
+    /// Same as [super::get_epsg_from_wkt_crs_bytes], but logs the error and returns `None` on failure
+    pub fn get_epsg_from_wkt_crs_bytes(bytes: &[u8]) -> Option<EpsgCRS> {
+        super::get_epsg_from_wkt_crs_bytes(bytes)
+            .inspect_err(|e| log!(Level::Debug, "Unable to parse the WKT-CRS: {e}"))
+            .ok()
+    }
+
+    /// Same as [super::get_epsg_from_geotiff_crs], but logs the error and returns `None` on failure
+    pub fn get_epsg_from_geotiff_crs(geotiff_crs_data: &GeoTiffCrs) -> Option<EpsgCRS> {
+        super::get_epsg_from_geotiff_crs(geotiff_crs_data)
+            .inspect_err(|e| log!(Level::Debug, "Unable to parse the GeoTiff-CRS: {e}"))
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use las::Reader;
+
+    #[test]
+    fn test_get_epsg_crs_wkt_vlr_autzen() {
+        let reader = Reader::from_path("testdata/autzen.copc.laz").expect("Cannot open reader");
+        let crs = reader
+            .header()
+            .get_epsg_crs()
+            .expect("Could not get epsg code")
+            .expect("The found EPSG was None");
+
+        assert!(crs.horizontal == 2992);
+        assert!(crs.vertical == Some(6360))
+    }
+
+    #[test]
+    fn test_get_epsg_crs_geotiff_vlr_norway() {
+        let reader = Reader::from_path("testdata/32-1-472-150-76.laz").expect("Cannot open reader");
+        let crs = reader.header().get_epsg_crs().unwrap().unwrap();
+        assert!(crs.horizontal == 25832);
+        assert!(crs.vertical == Some(5941));
+    }
+
+    #[test]
+    fn test_from_header() {
+        let reader = Reader::from_path("testdata/32-1-472-150-76.laz").expect("Cannot open reader");
+        assert_eq!(
+            EpsgCRS::from_header(reader.header()),
+            reader.header().get_epsg_crs()
+        );
+
+        let header = Builder::from((1, 4)).into_header().unwrap();
+        assert_eq!(EpsgCRS::from_header(&header), Ok(None));
+    }
+
+    #[test]
+    fn test_get_epsg_crs_wkt_vlr_autzen_las() {
+        let reader = Reader::from_path("testdata/autzen.las").expect("Cannot open reader");
+        let crs = reader
+            .header()
+            .get_epsg_crs()
+            .expect("Could not get epsg code")
+            .expect("The found EPSG was None");
+
+        assert!(crs.horizontal == 2994);
+        assert!(crs.vertical.is_none())
+    }
+
+    #[test]
+    fn test_crs_validator() {
+        let crs = EpsgCRS::new_unchecked(25832, None);
+        assert!(CrsValidator::default().validate(&crs).is_ok());
+
+        let validator = CrsValidator {
+            require_vertical: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            validator.validate(&crs),
+            Err(Error::MissingVerticalCrs)
+        ));
+
+        let validator = CrsValidator {
+            allowed_horizontal_range: 25828..=25838,
+            ..Default::default()
+        };
+        assert!(validator.validate(&crs).is_ok());
+        assert!(matches!(
+            validator.validate(&EpsgCRS::new_unchecked(2994, None)),
+            Err(Error::HorizontalCodeNotAllowed(2994))
+        ));
+
+        let user_defined = EpsgCRS::new_unchecked(32_767, None);
+        assert!(matches!(
+            CrsValidator::default().validate(&user_defined),
+            Err(Error::UserDefinedCrs)
+        ));
+        let validator = CrsValidator {
+            allow_user_defined: true,
+            ..Default::default()
+        };
+        assert!(validator.validate(&user_defined).is_ok());
+    }
+
+    #[test]
+    fn test_parse_epsg_token() {
+        assert_eq!(parse_epsg_token("EPSG:25832"), Some(25832));
+        assert_eq!(parse_epsg_token("EPSG::25832"), Some(25832));
+        assert_eq!(parse_epsg_token("EPSG:9.8.1:25832"), Some(25832));
+        assert_eq!(parse_epsg_token("EPSG:10:25832"), Some(25832));
+        assert_eq!(parse_epsg_token("EPSG:25832+5941"), Some(25832));
+        assert_eq!(parse_epsg_token("EPSG:25832 height"), Some(25832));
+        assert_eq!(parse_epsg_token("EPSG:"), None);
+        assert_eq!(parse_epsg_token("EPSG:abc"), None);
+        assert_eq!(parse_epsg_token("ESRI:102100"), None);
+
+        for citation in [
+            "ETRS89 / UTM zone 32N EPSG:25832 + NN2000 height EPSG:5941",
+            "ETRS89 / UTM zone 32N EPSG::25832 + NN2000 height EPSG::5941",
+            "ETRS89 / UTM zone 32N EPSG:9.8:25832 + NN2000 height EPSG:9.8:5941",
+        ] {
+            assert_eq!(
+                scan_citation_for_epsg(citation),
+                (Some(25832), Some(5941)),
+                "{citation}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_epsg_from_geotiff_compound_citation() {
+        let geotiff = GeoTiffCrs {
+            entries: vec![
+                GeoTiffKeyEntry {
+                    id: 1024,
+                    data: GeoTiffData::U16(1),
+                },
+                GeoTiffKeyEntry {
+                    id: 1026,
+                    data: GeoTiffData::String(
+                        "ETRS89 / UTM zone 32N EPSG:25832 + NN2000 height EPSG:5941".to_string(),
+                    ),
+                },
+            ],
+        };
+        let crs = get_epsg_from_geotiff_crs(&geotiff).unwrap();
+        assert_eq!(crs.get_horizontal(), 25832);
+        assert_eq!(crs.get_vertical(), Some(5941));
+    }
+
+    #[test]
+    fn test_deprecated_codes() {
+        let crs = EpsgCRS::new(3785, Some(5773)).unwrap();
+        assert!(crs.is_deprecated());
+        assert_eq!(
+            crs.successor(),
+            Some(EpsgCRS::new(3857, Some(5773)).unwrap())
+        );
+
+        let crs = EpsgCRS::new(25832, Some(5941)).unwrap();
+        assert!(!crs.is_deprecated());
+        assert_eq!(crs.successor(), None);
+    }
+
+    #[test]
+    fn test_is_national_grid() {
+        let crs = EpsgCRS::new(25832, Some(5941)).unwrap();
+        assert!(crs.is_national_grid("NO"));
+        assert!(crs.is_national_grid("de"));
+        assert!(!crs.is_national_grid("GB"));
+        assert!(!crs.is_national_grid("XX"));
+        assert!(EpsgCRS::new(27700, None).unwrap().is_national_grid("GB"));
+        assert!(!EpsgCRS::new(4326, None).unwrap().is_national_grid("NO"));
+    }
+
+    #[test]
+    fn test_from_projection_name() {
+        let crs = EpsgCRS::from_projection_name("WGS 84 / UTM Zone 32N").unwrap();
+        assert_eq!(crs, EpsgCRS::new(32632, None).unwrap());
+        let crs = EpsgCRS::from_projection_name("ETRS89 / UTM zone 32N").unwrap();
+        assert_eq!(crs.get_horizontal(), 25832);
+        let crs = EpsgCRS::from_projection_name("wgs84 / utm zone 33s").unwrap();
+        assert_eq!(crs.get_horizontal(), 32733);
+        let crs = EpsgCRS::from_projection_name("NAD83(HARN) / Oregon GIC Lambert (ft)").unwrap();
+        assert_eq!(crs.get_horizontal(), 2994);
+
+        assert_eq!(EpsgCRS::from_projection_name("WGS 84 / UTM zone 61N"), None);
+        assert_eq!(EpsgCRS::from_projection_name("ETRS89 / UTM zone 32S"), None);
+        assert_eq!(EpsgCRS::from_projection_name("WGS 84 + EGM96 height"), None);
+        assert_eq!(EpsgCRS::from_projection_name("WGS 84 / UTM zone 3é"), None);
+        assert_eq!(EpsgCRS::from_projection_name(""), None);
+    }
+
+    #[test]
+    fn test_get_epsg_from_geotiff_projected_citation_wins() {
+        let geotiff = GeoTiffCrs {
+            entries: vec![
+                GeoTiffKeyEntry {
+                    id: 1024,
+                    data: GeoTiffData::U16(1),
+                },
+                GeoTiffKeyEntry {
+                    id: 1026,
+                    data: GeoTiffData::String("WGS 84 / UTM zone 33S EPSG:32733".to_string()),
+                },
+                GeoTiffKeyEntry {
+                    id: 3073,
+                    data: GeoTiffData::String("ETRS89 / UTM zone 32N EPSG:25832".to_string()),
+                },
+            ],
+        };
+        let crs = get_epsg_from_geotiff_crs(&geotiff).unwrap();
+        assert_eq!(crs.get_horizontal(), 25832);
+        assert_eq!(crs.get_vertical(), None);
+    }
+
+    #[test]
+    fn test_get_epsg_from_wkt1_vert_cs_with_trailing_axis() {
+        let wkt = r#"COMPD_CS["NAD83(HARN) / Oregon GIC Lambert (ft) + NAVD88 height - Geoid12B (ft)",PROJCS["NAD83(HARN) / Oregon GIC Lambert (ft)",GEOGCS["NAD83(HARN)",DATUM["NAD83_High_Accuracy_Reference_Network",SPHEROID["GRS 1980",6378137,298.257222101,AUTHORITY["EPSG","7019"]],AUTHORITY["EPSG","6152"]],PRIMEM["Greenwich",0,AUTHORITY["EPSG","8901"]],UNIT["degree",0.0174532925199433,AUTHORITY["EPSG","9122"]],AUTHORITY["EPSG","4152"]],PROJECTION["Lambert_Conformal_Conic_2SP"],UNIT["foot",0.3048,AUTHORITY["EPSG","9002"]],AXIS["Easting",EAST],AXIS["Northing",NORTH],AUTHORITY["EPSG","2992"]],VERT_CS["NAVD88 height - Geoid12B (ft)",VERT_DATUM["North American Vertical Datum 1988",2005,AUTHORITY["EPSG","5103"]],UNIT["US survey foot",0.304800609601219,AUTHORITY["EPSG","9003"]],AUTHORITY["EPSG","6360"],AXIS["Gravity-related height",UP]]]"#;
+        let crs = get_epsg_from_wkt_crs_bytes(wkt.as_bytes()).unwrap();
+        assert_eq!(crs.get_horizontal(), 2992);
+        assert_eq!(crs.get_vertical(), Some(6360));
+
+        let wkt = r#"COMPD_CS["ETRS89 / UTM zone 32N + NN2000 height",PROJCS["ETRS89 / UTM zone 32N",AUTHORITY["EPSG","25832"]],VERT_CS["NN2000 height",VERT_DATUM["Norway Normal Null 2000",2005,AUTHORITY["EPSG","1096"]],AUTHORITY["EPSG",5941],AXIS["Up",UP]]]"#;
+        let crs = get_epsg_from_wkt_crs_bytes(wkt.as_bytes()).unwrap();
+        assert_eq!(crs.get_horizontal(), 25832);
+        assert_eq!(crs.get_vertical(), Some(5941));
+    }
+
+    #[test]
+    fn test_detect_wkt_version() {
+        assert_eq!(
+            detect_wkt_version(br#"PROJCS["ETRS89 / UTM zone 32N",AUTHORITY["EPSG","25832"]]"#),
+            Some(WktVersion::Wkt1)
+        );
+        assert_eq!(
+            detect_wkt_version(br#"PROJCRS["ETRS89 / UTM zone 32N",ID["EPSG",25832]]"#),
+            Some(WktVersion::Wkt2)
+        );
+        assert_eq!(detect_wkt_version(b"not a crs"), None);
+    }
+
+    #[cfg(feature = "validate")]
+    #[test]
+    fn test_new_typed_checked() {
+        use crate::CrsType;
+
+        let crs = EpsgCRS::new_typed_checked(25832, Some(5941)).unwrap();
+        assert_eq!(crs.get_horizontal(), 25832);
+        assert_eq!(crs.get_vertical(), Some(5941));
+
+        assert_eq!(
+            EpsgCRS::new_typed_checked(5941, None),
+            Err(Error::WrongCrsType {
+                code: 5941,
+                expected: CrsType::Horizontal
+            })
+        );
+        assert_eq!(
+            EpsgCRS::new_typed_checked(25832, Some(4326)),
+            Err(Error::WrongCrsType {
+                code: 4326,
+                expected: CrsType::Vertical
+            })
+        );
+
+        // codes in neither the registry nor the vertical table are only range checked
+        assert!(EpsgCRS::new_typed_checked(1025, Some(1026)).is_ok());
+    }
+
+    #[test]
+    fn test_decode_vlr_bytes_latin1() {
+        assert_eq!(decode_vlr_bytes("Bogotá".as_bytes()), "Bogotá");
+        // "Bogotá" in Latin-1
+        assert_eq!(decode_vlr_bytes(b"Bogot\xe1"), "Bogotá");
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(
+            EpsgCRS::new_unchecked(25832, Some(5941)).to_json(),
+            r#"{"horizontal":25832,"vertical":5941}"#
+        );
+        assert_eq!(
+            EpsgCRS::new_unchecked(25832, None).to_json(),
+            r#"{"horizontal":25832,"vertical":null}"#
+        );
+    }
+
+    #[test]
+    fn test_get_epsg_crs_wkt_vlr_after_laszip_vlr() {
+        let mut builder = Builder::from((1, 4));
+        builder.vlrs.push(Vlr {
+            user_id: "laszip encoded".to_string(),
+            record_id: 22204,
+            description: "laszip".to_string(),
+            data: vec![0; 34],
+        });
+        builder.vlrs.push(Vlr {
+            user_id: "LASF_Projection".to_string(),
+            record_id: 2112,
+            description: "WKT".to_string(),
+            data: br#"PROJCS["ETRS89 / UTM zone 32N",AUTHORITY["EPSG","25832"]]"#.to_vec(),
+        });
+        builder.has_wkt_crs = true;
+        let header = builder.into_header().unwrap();
+
+        let crs = header.get_epsg_crs().unwrap().unwrap();
+        assert_eq!(crs.get_horizontal(), 25832);
+        assert!(crs.is_horizontal_only());
+    }
+
+    #[test]
+    fn test_get_epsg_from_wkt_crs_bytes_with_diagnostics() {
+        let wkt = br#"COMPD_CS["ETRS89 / UTM zone 32N + unknown",PROJCS["ETRS89 / UTM zone 32N",GEOGCS["ETRS89",AUTHORITY["EPSG","4258"]],AUTHORITY["EPSG","25832"]],VERT_CS["unknown",VERT_DATUM["unknown",2005],AUTHORITY["EPSG","0"]]]"#;
+        let (crs, diagnostics) = get_epsg_from_wkt_crs_bytes_with_diagnostics(wkt);
+        let crs = crs.unwrap();
+        assert_eq!(crs.get_horizontal(), 25832);
+        assert_eq!(crs.get_vertical(), None);
+
+        assert_eq!(diagnostics.epsg_authority_nodes, 3);
+        assert_eq!(diagnostics.vertical_keyword.as_deref(), Some("VERT_CS"));
+        assert_eq!(diagnostics.parsed_vertical, Some(0));
+        assert!(diagnostics.vertical_from_authority);
+        assert!(diagnostics.vertical_defaulted_to_none);
+        assert!(!diagnostics.used_fallback_heuristic);
+    }
+
+    #[test]
+    fn test_wkt_diagnostics_used_fallback_heuristic() {
+        let wkt = br#"PROJCS["EPSG 25832"]"#;
+        let (crs, diagnostics) = get_epsg_from_wkt_crs_bytes_with_diagnostics(wkt);
+        assert_eq!(crs.unwrap().get_horizontal(), 25832);
+        assert!(diagnostics.used_fallback_heuristic);
+
+        // authoritative horizontal, heuristic vertical
+        let wkt = br#"COMPD_CS["x",PROJCS["x",AUTHORITY["EPSG","25832"]],VERT_CS["NN2000 5941"]]"#;
+        let (crs, diagnostics) = get_epsg_from_wkt_crs_bytes_with_diagnostics(wkt);
+        assert_eq!(crs.unwrap().get_vertical(), Some(5941));
+        assert!(!diagnostics.vertical_from_authority);
+        assert!(diagnostics.used_fallback_heuristic);
+
+        let wkt = br#"PROJCS["ETRS89 / UTM zone 32N",AUTHORITY["EPSG","25832"]]"#;
+        let (_, diagnostics) = get_epsg_from_wkt_crs_bytes_with_diagnostics(wkt);
+        assert!(!diagnostics.used_fallback_heuristic);
+    }
+
+    #[test]
+    fn test_wkt_authority_nodes_autzen() {
+        let reader = Reader::from_path("testdata/autzen.copc.laz").expect("Cannot open reader");
+        let wkt = reader
+            .header()
+            .get_wkt_crs_bytes()
+            .expect("The file has no WKT-CRS");
+
+        let nodes = wkt_authority_nodes(wkt);
+        let expected = [
+            ("SPHEROID", 7019),
+            ("DATUM", 6269),
+            ("PRIMEM", 8901),
+            ("UNIT", 9122),
+            ("GEOGCS", 4269),
+            ("UNIT", 9002),
+            ("PROJCS", 2992),
+            ("VERT_DATUM", 5103),
+            ("UNIT", 9003),
+            ("VERT_CS", 6360),
+        ];
+        assert_eq!(nodes.len(), expected.len());
+        for ((keyword, code), (expected_keyword, expected_code)) in nodes.iter().zip(expected) {
+            assert_eq!(keyword, expected_keyword);
+            assert_eq!(*code, expected_code);
+        }
+    }
+
+    #[test]
+    fn test_get_epsg_from_pretty_printed_wkt() {
+        let wkt = "COMPOUNDCRS[\"ETRS89 / UTM zone 32N + NN2000 height\",\r\n\tPROJCRS[\"ETRS89 / UTM zone 32N\",\r\n\t\tBASEGEOGCRS[\"ETRS89\",\r\n\t\t\tDATUM[\"European Terrestrial Reference System 1989\",\r\n\t\t\t\tELLIPSOID[\"GRS 1980\", 6378137, 298.257222101]]],\r\n\t\tID[\"EPSG\", 25832]],\r\n\tVERT\r\n\tCRS[\"NN2000 height\",\r\n\t\tVDATUM[\"Norway Normal Null 2000\"],\r\n\t\tID[\"EPSG\", 5941]]\r\n]\r\n";
+        let crs = get_epsg_from_wkt_crs_bytes(wkt.as_bytes()).unwrap();
+        assert_eq!(crs.get_horizontal(), 25832);
+        assert_eq!(crs.get_vertical(), Some(5941));
+    }
+
+    #[cfg(feature = "validate")]
+    #[test]
+    fn test_describe() {
+        assert_eq!(
+            EpsgCRS::new_unchecked(25832, None).describe(),
+            "ETRS89 / UTM zone 32N (EPSG:25832)"
+        );
+
+        assert_eq!(
+            EpsgCRS::new_unchecked(25832, Some(5941)).describe(),
+            "ETRS89 / UTM zone 32N (EPSG:25832) + NN2000 height (EPSG:5941)"
+        );
+
+        assert_eq!(
+            EpsgCRS::new_unchecked(1025, None).describe(),
+            "EPSG:1025 (unknown)"
+        );
+    }
+
+    #[test]
+    fn test_error_eq() {
+        let geotiff = GeoTiffCrs {
+            entries: vec![GeoTiffKeyEntry {
+                id: 1024,
+                data: GeoTiffData::U16(32_767),
+            }],
+        };
+        assert_eq!(
+            get_epsg_from_geotiff_crs(&geotiff),
+            Err(Error::UserDefinedCrs)
+        );
+        assert_eq!(
+            Error::LasError(las::Error::UnreadableGeoTiffCrs),
+            Error::LasError(las::Error::UnreadableGeoTiffCrs)
+        );
+        assert_ne!(Error::SetBadCode(1), Error::SetBadCode(2));
+        assert_ne!(Error::UserDefinedCrs, Error::UnreadableWktCrs);
+    }
+
+    #[test]
+    fn test_get_epsg_crs_from_reader_prefix() {
+        // simulate a memory-mapped file with only the header and the VLRs available
+        let bytes = std::fs::read("testdata/autzen.las").expect("Cannot read file");
+        let offset_to_point_data = u32::from_le_bytes(bytes[96..100].try_into().unwrap()) as usize;
+        let crs = get_epsg_crs_from_reader(std::io::Cursor::new(&bytes[..offset_to_point_data]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(crs.get_horizontal(), 2994);
+
+        // the EVLRs at the end of the 1.4 file are not in the prefix
+        let bytes = std::fs::read("testdata/autzen.copc.laz").expect("Cannot read file");
+        let offset_to_point_data = u32::from_le_bytes(bytes[96..100].try_into().unwrap()) as usize;
+        let crs = get_epsg_crs_from_reader(std::io::Cursor::new(&bytes[..offset_to_point_data]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(crs.get_horizontal(), 2992);
+        assert_eq!(crs.get_vertical(), Some(6360));
+    }
+
+    #[test]
+    fn test_from_str_lenient() {
+        let horizontal = EpsgCRS::new_unchecked(4326, None);
+        for s in [
+            "4326",
+            "EPSG:4326",
+            "epsg:4326",
+            "EPSG::4326",
+            " urn:ogc:def:crs:EPSG::4326 ",
+            "urn:ogc:def:crs:EPSG:9.9.1:4326",
+        ] {
+            assert_eq!(EpsgCRS::from_str_lenient(s), Ok(horizontal), "{s}");
+        }
+
+        let compound = EpsgCRS::new_unchecked(4326, Some(5773));
+        for s in [
+            "4326+5773",
+            "EPSG:4326+5773",
+            "EPSG:4326,EPSG:5773",
+            "urn:ogc:def:crs,crs:EPSG::4326,crs:EPSG::5773",
+        ] {
+            assert_eq!(EpsgCRS::from_str_lenient(s), Ok(compound), "{s}");
+        }
+
+        for s in ["ESRI:102100", "EPSG:4326+5773+1234"] {
+            assert!(matches!(
+                EpsgCRS::from_str_lenient(s),
+                Err(Error::InvalidCrsString(_))
+            ));
+        }
+        for s in ["", "EPSG:abc", "EPSG:70000"] {
+            assert!(matches!(
+                EpsgCRS::from_str_lenient(s),
+                Err(Error::CodeParseError(_))
+            ));
+        }
+        assert_eq!(EpsgCRS::from_str_lenient("EPSG:0"), Err(Error::BadEPSGCrs));
+    }
+
+    #[test]
+    fn test_get_epsg_crs_no_crs_vs_malformed() {
+        let header = Builder::from((1, 4)).into_header().unwrap();
+        assert_eq!(header.get_epsg_crs(), Ok(None));
+
+        // a GeoTiff ascii params VLR without the key directory VLR
+        let mut builder = Builder::from((1, 4));
+        builder.vlrs.push(Vlr {
+            user_id: "LASF_Projection".to_string(),
+            record_id: 34737,
+            description: "GeoTiff ascii params".to_string(),
+            data: b"ETRS89 / UTM zone 32N|\0".to_vec(),
+        });
+        let header = builder.into_header().unwrap();
+        assert_eq!(header.get_epsg_crs(), Err(Error::MalformedCrsVlr));
+    }
+
+    #[test]
+    fn test_get_epsg_crs_with_fallback() {
+        let reader = Reader::from_path("testdata/autzen.las").expect("Cannot open reader");
+        let mut builder = Builder::from(reader.header().clone());
+        // a truncated WKT-CRS alongside the valid GeoTiff-CRS
+        builder.vlrs.push(Vlr {
+            user_id: "LASF_Projection".to_string(),
+            record_id: 2112,
+            description: "WKT".to_string(),
+            data: br#"PROJCS["NAD83(HARN) / Oregon Lambert (ft)",GEOGCS["NAD83(H"#.to_vec(),
+        });
+        let header = builder.into_header().unwrap();
+
+        assert!(matches!(
+            header.get_epsg_crs(),
+            Err(Error::BadHorizontalCodeParsed(_))
+        ));
+        let crs = get_epsg_crs_with_fallback(&header).unwrap().unwrap();
+        assert_eq!(crs.get_horizontal(), 2994);
+    }
+
+    #[test]
+    fn test_cache_key() {
+        assert_eq!(
+            EpsgCRS::new_unchecked(25832, Some(5941)).cache_key(),
+            "h25832_v5941"
+        );
+        assert_eq!(EpsgCRS::new_unchecked(25832, None).cache_key(), "h25832");
+    }
+
+    #[test]
+    fn test_get_epsg_crs_paired_wkt_vlrs() {
+        let mut builder = Builder::from((1, 4));
+        builder.vlrs.push(Vlr {
+            user_id: "LASF_Projection".to_string(),
+            record_id: 2112,
+            description: "WKT".to_string(),
+            data: br#"PROJCS["ETRS89 / UTM zone 32N",AUTHORITY["EPSG","25832"]]"#.to_vec(),
+        });
+        builder.vlrs.push(Vlr {
+            user_id: "LASF_Projection".to_string(),
+            record_id: 2113,
+            description: "WKT".to_string(),
+            data: br#"VERT_CS["NN2000 height",VERT_DATUM["Norway Normal Null 2000",2005,AUTHORITY["EPSG","1096"]],AUTHORITY["EPSG","5941"]]"#.to_vec(),
+        });
+        builder.has_wkt_crs = true;
+        let header = builder.into_header().unwrap();
+
+        let crs = header.get_epsg_crs().unwrap().unwrap();
+        assert!(crs.is_compound());
+        assert_eq!(crs.get_horizontal(), 25832);
+        assert_eq!(crs.get_vertical(), Some(5941));
+    }
+
+    #[test]
+    fn test_get_epsg_crs_unpaired_wkt_vlrs() {
+        let compound = br#"COMPD_CS["ETRS89 / UTM zone 32N + NN2000 height",PROJCS["ETRS89 / UTM zone 32N",AUTHORITY["EPSG","25832"]],VERT_CS["NN2000 height",AUTHORITY["EPSG","5941"]]]"#;
+        let wkt_vlr = |record_id: u16, data: &[u8]| Vlr {
+            user_id: "LASF_Projection".to_string(),
+            record_id,
+            description: "WKT".to_string(),
+            data: data.to_vec(),
+        };
+
+        // a duplicated WKT-CRS (E)VLR and an unrelated compound WKT (E)VLR are not paired
+        let mut builder = Builder::from((1, 4));
+        builder.vlrs.push(wkt_vlr(
+            2112,
+            br#"PROJCS["ETRS89 / UTM zone 32N",AUTHORITY["EPSG","25832"]]"#,
+        ));
+        builder.vlrs.push(wkt_vlr(2112, compound));
+        builder.vlrs.push(wkt_vlr(4000, compound));
+        builder.has_wkt_crs = true;
+        let header = builder.into_header().unwrap();
+
+        let crs = header.get_epsg_crs().unwrap().unwrap();
+        assert_eq!(crs.get_horizontal(), 25832);
+        assert_eq!(crs.get_vertical(), None);
+        assert_eq!(get_vertical_epsg(&header), Ok(None));
+    }
+
+    #[test]
+    fn test_is_in_region() {
+        let reader = Reader::from_path("testdata/32-1-472-150-76.laz").unwrap();
+        let bounds = reader.header().bounds();
+        let bbox = (bounds.min.x, bounds.min.y, bounds.max.x, bounds.max.y);
+        let in_region = |code| EpsgCRS::new(code, None).unwrap().is_in_region(bbox);
+        assert_eq!(in_region(25832), Some(true));
+        assert_eq!(in_region(4326), Some(false));
+        assert_eq!(in_region(27700), Some(false));
+        assert_eq!(in_region(2994), None);
+
+        let bbox = (10.5, 59.8, 10.9, 60.0);
+        assert_eq!(
+            EpsgCRS::new(4258, None).unwrap().is_in_region(bbox),
+            Some(true)
+        );
+
+        // a tile near the equator on the northern hemisphere is below the false northing of the southern zones
+        let bbox = (500_000.0, 500_000.0, 501_000.0, 501_000.0);
+        assert_eq!(
+            EpsgCRS::new(32633, None).unwrap().is_in_region(bbox),
+            Some(true)
+        );
+        assert_eq!(
+            EpsgCRS::new(32733, None).unwrap().is_in_region(bbox),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_to_json_with_path() {
+        let crs = EpsgCRS::new(25832, Some(5941)).unwrap();
+        assert_eq!(
+            crs.to_json_with_path(std::path::Path::new("tiles/a.laz")),
+            r#"{"path":"tiles/a.laz","crs":{"horizontal":25832,"vertical":5941}}"#
+        );
+        assert_eq!(
+            crs.to_json_with_path(std::path::Path::new("a \"b\"\\c\n\u{1}\u{7f}.laz")),
+            r#"{"path":"a \"b\"\\c\n\u0001\u007f.laz","crs":{"horizontal":25832,"vertical":5941}}"#
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            let path = std::path::Path::new(std::ffi::OsStr::from_bytes(b"a\xff.laz"));
+            assert!(
+                crs.to_json_with_path(path)
+                    .starts_with("{\"path\":\"a\u{fffd}.laz\"")
+            );
+        }
+    }
+
+    #[test]
+    fn test_epsg_crs_builder() {
+        let crs = EpsgCRSBuilder::new().horizontal(25832).build().unwrap();
+        assert_eq!(crs, EpsgCRS::new(25832, None).unwrap());
+
+        let crs = EpsgCRSBuilder::new()
+            .vertical(Some(5941))
+            .horizontal(25832)
+            .build()
+            .unwrap();
+        assert_eq!(crs, EpsgCRS::new(25832, Some(5941)).unwrap());
+
+        let builder = EpsgCRSBuilder::new().horizontal(25832).vertical(Some(0));
+        assert_eq!(builder.build(), Err(Error::BadEPSGCrs));
+        assert_eq!(
+            builder.vertical(None).build(),
+            Ok(crs.with_vertical_option(None).unwrap())
+        );
+        assert_eq!(
+            EpsgCRSBuilder::new().build(),
+            Err(Error::MissingHorizontalCrs)
+        );
+    }
+
+    #[test]
+    fn test_tuple_conversions() {
+        let crs = EpsgCRS::new(25832, Some(5941)).unwrap();
+        let (horizontal, vertical) = crs.as_tuple();
+        assert_eq!((horizontal, vertical), (25832, Some(5941)));
+
+        let tuple: (u16, Option<u16>) = crs.into();
+        assert_eq!(EpsgCRS::try_from(tuple), Ok(crs));
+        assert_eq!(EpsgCRS::try_from((0, None)), Err(Error::BadEPSGCrs));
+    }
+
+    #[test]
+    fn test_get_wkt_metadata_datum_name() {
+        let harn = br#"COMPD_CS["NAD83(HARN) / Oregon GIC Lambert (ft) + NAVD88 height",PROJCS["NAD83(HARN) / Oregon GIC Lambert (ft)",GEOGCS["NAD83(HARN)",DATUM["NAD83_High_Accuracy_Reference_Network",SPHEROID["GRS 1980",6378137,298.257222101]],AUTHORITY["EPSG","4152"]],AUTHORITY["EPSG","2994"]],VERT_CS["NAVD88 height",VERT_DATUM["North American Vertical Datum 1988",2005],AUTHORITY["EPSG","5703"]]]"#;
+        let metadata = get_wkt_metadata(harn).unwrap();
+        assert_eq!(
+            metadata.datum_name.as_deref(),
+            Some("NAD83_High_Accuracy_Reference_Network")
+        );
+
+        let nad83 = br#"PROJCS["NAD83 / Oregon GIC Lambert (ft)",GEOGCS["NAD83",DATUM["North_American_Datum_1983",SPHEROID["GRS 1980",6378137,298.257222101]]],AUTHORITY["EPSG","2992"]]"#;
+        let metadata = get_wkt_metadata(nad83).unwrap();
+        assert_eq!(
+            metadata.datum_name.as_deref(),
+            Some("North_American_Datum_1983")
+        );
+
+        let vertical_only = br#"VERT_CS["NAVD88 height",VERT_DATUM["North American Vertical Datum 1988",2005],AUTHORITY["EPSG","5703"]]"#;
+        assert_eq!(get_wkt_metadata(vertical_only), Ok(WktMetadata::default()));
+    }
+
+    #[test]
+    fn test_get_epsg_from_mixed_wkt_dialects() {
+        let wkt = br#"PROJCS["ETRS89 / UTM zone 33N",AUTHORITY["EPSG","25833"]]PROJCRS["ETRS89 / UTM zone 32N",BASEGEOGCRS["ETRS89",ID["EPSG",4258]],ID["EPSG",25832]]"#;
+        assert_eq!(
+            get_epsg_from_wkt_crs_bytes(wkt),
+            Err(Error::MixedWktDialects)
+        );
+
+        // keywords in names and sub-node keywords are not CRS nodes
+        let wkt = br#"PROJCRS["PROJCS ETRS89 / UTM zone 32N",BASEGEOGCRS["ETRS89",ID["EPSG",4258]],ID["EPSG",25832]]"#;
+        assert_eq!(
+            get_epsg_from_wkt_crs_bytes(wkt),
+            Ok(EpsgCRS::new(25832, None).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_get_epsg_from_wkt_lowercase_authority() {
+        let wkt = br#"COMPD_CS["ETRS89 / UTM zone 32N + NN2000 height",PROJCS["ETRS89 / UTM zone 32N",GEOGCS["ETRS89",AUTHORITY["epsg","4258"]],AUTHORITY["epsg","25832"],AXIS["Easting",EAST]],VERT_CS["NN2000 height",AUTHORITY["Epsg","5941"],AXIS["Up",UP]]]"#;
+        let (crs, diagnostics) = get_epsg_from_wkt_crs_bytes_with_diagnostics(wkt);
+        assert_eq!(crs, Ok(EpsgCRS::new(25832, Some(5941)).unwrap()));
+        assert_eq!(diagnostics.epsg_authority_nodes, 3);
+        assert!(!diagnostics.used_fallback_heuristic);
+    }
+
+    #[test]
+    fn test_get_epsg_from_wkt_geographic_root() {
+        // the datum authority comes after the root authority, so the trailing digits would give the datum code
+        let wkt = r#"GEOGCRS["ETRS89",ID["EPSG",4258],DATUM["European Terrestrial Reference System 1989",ELLIPSOID["GRS 1980",6378137,298.257222101],ID["EPSG",6258]]]"#;
+        let (crs, diagnostics) = get_epsg_from_wkt_crs_bytes_with_diagnostics(wkt.as_bytes());
+        assert_eq!(crs, Ok(EpsgCRS::new(4258, None).unwrap()));
+        assert_eq!(diagnostics.horizontal_keyword.as_deref(), Some("GEOGCRS"));
+        assert!(!diagnostics.used_fallback_heuristic);
+
+        let wkt = r#"GEOGCS["WGS 84",DATUM["WGS_1984",SPHEROID["WGS 84",6378137,298.257223563,AUTHORITY["EPSG","7030"]],AUTHORITY["EPSG","6326"]],PRIMEM["Greenwich",0],UNIT["degree",0.0174532925199433],AUTHORITY["EPSG","4326"]]"#;
+        let (crs, diagnostics) = get_epsg_from_wkt_crs_bytes_with_diagnostics(wkt.as_bytes());
+        assert_eq!(crs, Ok(EpsgCRS::new(4326, None).unwrap()));
+        assert_eq!(diagnostics.horizontal_keyword.as_deref(), Some("GEOGCS"));
+
+        let wkt = r#"PROJCRS["ETRS89 / UTM zone 32N",BASEGEOGCRS["ETRS89",ID["EPSG",4258]],ID["EPSG",25832]]"#;
+        let (_, diagnostics) = get_epsg_from_wkt_crs_bytes_with_diagnostics(wkt.as_bytes());
+        assert_eq!(diagnostics.horizontal_keyword.as_deref(), Some("PROJCRS"));
+    }
+
+    #[test]
+    fn test_get_epsg_from_wkt2_compound_with_time_member() {
+        let wkt = r#"COMPOUNDCRS["WGS 84 + EGM96 height + GPS time",GEOGCRS["WGS 84",DATUM["World Geodetic System 1984",ELLIPSOID["WGS 84",6378137,298.257223563]],CS[ellipsoidal,2],ID["EPSG",4326]],VERTCRS["EGM96 height",VDATUM["EGM96 geoid"],CS[vertical,1],ID["EPSG",5773]],TIMECRS["GPS time",TDATUM["Time origin",TIMEORIGIN[1980-01-06T00:00:00.0Z]],CS[TemporalMeasure,1],AXIS["time (T)",future],TIMEUNIT["second",1]]]"#;
+        let (crs, diagnostics) = get_epsg_from_wkt_crs_bytes_with_diagnostics(wkt.as_bytes());
+        assert_eq!(crs, Ok(EpsgCRS::new(4326, Some(5773)).unwrap()));
+        assert!(!diagnostics.used_fallback_heuristic);
+        assert_eq!(
+            wkt_authority_nodes(wkt.as_bytes()),
+            vec![("GEOGCRS".to_string(), 4326), ("VERTCRS".to_string(), 5773)]
+        );
+
+        // without ID nodes, the trailing digits of the time member must not be read as the vertical code
+        let wkt = r#"COMPOUNDCRS["WGS 84 + EGM96 height + time",GEOGCRS["EPSG:4326",4326],VERTCRS["EPSG:5773",5773],TIMECRS["time",TIMEUNIT["second",1980]]]"#;
+        let crs = get_epsg_from_wkt_crs_bytes(wkt.as_bytes()).unwrap();
+        assert_eq!(crs.get_horizontal(), 4326);
+        assert_eq!(crs.get_vertical(), Some(5773));
+
+        // the time member may come before the vertical member
+        let wkt = r#"COMPOUNDCRS["time + WGS 84 + EGM96 height",TIMECRS["time",TIMEUNIT["second",1980]],GEOGCRS["EPSG:4326",4326],VERTCRS["EPSG:5773",5773]]"#;
+        let crs = get_epsg_from_wkt_crs_bytes(wkt.as_bytes()).unwrap();
+        assert_eq!(crs.get_horizontal(), 4326);
+        assert_eq!(crs.get_vertical(), Some(5773));
+    }
+
+    #[test]
+    fn test_to_wkt1_round_trip() {
+        for crs in [
+            EpsgCRS::new(25832, Some(5941)).unwrap(),
+            EpsgCRS::new(2994, None).unwrap(),
+            EpsgCRS::new(4326, Some(5773)).unwrap(),
+        ] {
+            let wkt = crs.to_wkt1();
+            assert_eq!(detect_wkt_version(wkt.as_bytes()), Some(WktVersion::Wkt1));
+            assert_eq!(get_epsg_from_wkt_crs_bytes(wkt.as_bytes()), Ok(crs));
+        }
+        assert_eq!(
+            EpsgCRS::new(4326, None).unwrap().to_wkt1(),
+            r#"GEOGCS["EPSG:4326",AUTHORITY["EPSG","4326"]]"#
+        );
+    }
+
+    #[test]
+    fn test_geotiff_las_reader_and_raw_bytes_agree() {
+        let expected = EpsgCRS::new(2994, None).unwrap();
+
+        let bytes = std::fs::read("testdata/autzen.las").unwrap();
+        let from_bytes = get_epsg_crs_from_reader(std::io::Cursor::new(&bytes[..1994]));
+        assert_eq!(from_bytes, Ok(Some(expected)));
+
+        let reader = Reader::from_path("testdata/autzen.las").unwrap();
+        let header = reader.header();
+        assert_eq!(header.get_epsg_crs(), Ok(Some(expected)));
+        let geotiff = header.get_geotiff_crs().unwrap().unwrap();
+        assert_eq!(get_epsg_from_geotiff_crs(&geotiff), Ok(expected));
     }
-    Ok(code)
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::ParseEpsgCRS;
-    use las::Reader;
+    #[test]
+    fn test_summarize_crs() {
+        let utm = EpsgCRS::new(25832, Some(5941)).unwrap();
+        let oregon = EpsgCRS::new(2994, None).unwrap();
+        let results = vec![
+            (PathBuf::from("a.laz"), Ok(Some(utm))),
+            (PathBuf::from("b.laz"), Ok(Some(utm))),
+            (PathBuf::from("c.las"), Ok(Some(oregon))),
+            (PathBuf::from("d.las"), Ok(None)),
+            (PathBuf::from("e.las"), Err(Error::UnreadableWktCrs)),
+        ];
+        let summary = summarize_crs(&results);
+        assert_eq!(summary.len(), 3);
+        assert_eq!(summary[&Some(utm)], 2);
+        assert_eq!(summary[&Some(oregon)], 1);
+        assert_eq!(summary[&None], 1);
+        assert!(summarize_crs(&[]).is_empty());
+    }
 
     #[test]
-    fn test_get_epsg_crs_wkt_vlr_autzen() {
-        let reader = Reader::from_path("testdata/autzen.copc.laz").expect("Cannot open reader");
-        let crs = reader
-            .header()
-            .get_epsg_crs()
-            .expect("Could not get epsg code")
-            .expect("The found EPSG was None");
+    fn test_get_epsg_from_raw_vlrs() {
+        let read_raw_vlrs = |path| {
+            let mut file = std::io::BufReader::new(std::fs::File::open(path).unwrap());
+            let raw_header = las::raw::Header::read_from(&mut file).unwrap();
+            file.seek(SeekFrom::Start(raw_header.header_size as u64))
+                .unwrap();
+            (0..raw_header.number_of_variable_length_records)
+                .map(|_| las::raw::Vlr::read_from(&mut file, false).unwrap())
+                .collect::<Vec<_>>()
+        };
 
-        assert!(crs.horizontal == 2992);
-        assert!(crs.vertical == Some(6360))
+        let vlrs = read_raw_vlrs("testdata/autzen.las");
+        assert_eq!(
+            get_epsg_from_raw_vlrs(&vlrs),
+            Ok(Some(EpsgCRS::new(2994, None).unwrap()))
+        );
+
+        let vlrs = read_raw_vlrs("testdata/autzen.copc.laz");
+        assert_eq!(
+            get_epsg_from_raw_vlrs(&vlrs),
+            Ok(Some(EpsgCRS::new(2992, Some(6360)).unwrap()))
+        );
+
+        let vlrs = read_raw_vlrs("testdata/32-1-472-150-76.laz");
+        assert_eq!(
+            get_epsg_from_raw_vlrs(&vlrs),
+            Ok(Some(EpsgCRS::new(25832, Some(5941)).unwrap()))
+        );
+
+        assert_eq!(get_epsg_from_raw_vlrs(&[]), Ok(None));
     }
 
     #[test]
-    fn test_get_epsg_crs_geotiff_vlr_norway() {
+    fn test_best_effort() {
+        let reader = Reader::from_path("testdata/autzen.copc.laz").unwrap();
+        assert_eq!(
+            best_effort::get_epsg(reader.header()),
+            Some(EpsgCRS::new(2992, Some(6360)).unwrap())
+        );
+
+        // a broken WKT-CRS alongside a valid GeoTiff-CRS
+        let mut builder = Builder::from((1, 4));
+        builder.vlrs.push(Vlr {
+            user_id: "LASF_Projection".to_string(),
+            record_id: 2112,
+            description: "WKT".to_string(),
+            data: b"not WKT".to_vec(),
+        });
+        builder.vlrs.push(Vlr {
+            user_id: "LASF_Projection".to_string(),
+            record_id: 34735,
+            description: "GeoTiff GeoKeyDirectoryTag".to_string(),
+            data: EpsgCRS::new(25832, None)
+                .unwrap()
+                .encode_as_geotiff_vlr_bytes(),
+        });
+        builder.has_wkt_crs = true;
+        let header = builder.into_header().unwrap();
+        assert_eq!(
+            best_effort::get_epsg(&header),
+            Some(EpsgCRS::new(25832, None).unwrap())
+        );
+
+        let header = Builder::from((1, 4)).into_header().unwrap();
+        assert_eq!(best_effort::get_epsg(&header), None);
+        assert_eq!(best_effort::get_epsg_from_wkt_crs_bytes(b"not WKT"), None);
+    }
+
+    #[test]
+    fn test_get_vertical_epsg() {
+        let reader = Reader::from_path("testdata/autzen.copc.laz").unwrap();
+        assert_eq!(get_vertical_epsg(reader.header()), Ok(Some(6360)));
+
+        let reader = Reader::from_path("testdata/32-1-472-150-76.laz").unwrap();
+        assert_eq!(get_vertical_epsg(reader.header()), Ok(Some(5941)));
+
+        let reader = Reader::from_path("testdata/autzen.las").unwrap();
+        assert_eq!(get_vertical_epsg(reader.header()), Ok(None));
+
+        let header = Builder::from((1, 4)).into_header().unwrap();
+        assert_eq!(get_vertical_epsg(&header), Ok(None));
+    }
+
+    #[test]
+    fn test_wkt_crs_cached() {
+        let reader = Reader::from_path("testdata/autzen.copc.laz").unwrap();
+        let cached = WktCrsCached::new(reader.header().clone());
+        let expected = EpsgCRS::new(2992, Some(6360)).unwrap();
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| assert_eq!(cached.get_epsg_crs(), &Ok(Some(expected))));
+            }
+        });
+        // the same cached result is returned on every call
+        assert!(std::ptr::eq(cached.get_epsg_crs(), cached.get_epsg_crs()));
+        assert_eq!(cached.into_header().get_epsg_crs(), Ok(Some(expected)));
+    }
+
+    #[test]
+    fn test_copy_crs_vlrs_to() {
+        let reader = Reader::from_path("testdata/autzen.copc.laz").unwrap();
+        let reference = reader.header();
+
+        let mut builder = Builder::from((1, 4));
+        write_vlr_bytes_to_header(
+            &mut builder,
+            "LASF_Projection",
+            34735,
+            EpsgCRS::new(4326, None)
+                .unwrap()
+                .encode_as_geotiff_vlr_bytes(),
+        )
+        .unwrap();
+        reference.copy_crs_vlrs_to(&mut builder).unwrap();
+        let header = builder.into_header().unwrap();
+
+        assert_eq!(header.get_wkt_crs_bytes(), reference.get_wkt_crs_bytes());
+        assert!(header.get_geotiff_crs().unwrap().is_none());
+        assert!(header.has_wkt_crs());
+        assert_eq!(header.get_epsg_crs(), reference.get_epsg_crs());
+
+        let empty = Builder::from((1, 4)).into_header().unwrap();
+        let mut builder = Builder::from((1, 4));
+        assert_eq!(empty.copy_crs_vlrs_to(&mut builder), Err(Error::MissingCrs));
+    }
+
+    #[test]
+    fn test_remove_all_crs_vlrs() {
+        let reader = Reader::from_path("testdata/autzen.copc.laz").unwrap();
+        let mut header = reader.header().clone();
+        let other_vlrs = header.vlrs().iter().filter(|vlr| !is_crs_vlr(vlr)).count();
+
+        assert_eq!(remove_all_crs_vlrs(&mut header), 1);
+        assert!(!header.has_wkt_crs());
+        assert_eq!(header.vlrs().len(), other_vlrs);
+        assert_eq!(header.get_epsg_crs(), Ok(None));
+        assert_eq!(remove_all_crs_vlrs(&mut header), 0);
+
+        let reader = Reader::from_path("testdata/autzen.las").unwrap();
+        let mut header = reader.header().clone();
+        assert!(remove_all_crs_vlrs(&mut header) > 0);
+        assert_eq!(header.get_epsg_crs(), Ok(None));
+    }
+
+    #[test]
+    fn test_write_vlr_bytes_to_header() {
+        let mut builder = Builder::from((1, 4));
+        let crs = EpsgCRS::new(25832, Some(5941)).unwrap();
+        write_vlr_bytes_to_header(
+            &mut builder,
+            "LASF_Projection",
+            2112,
+            b"PROJCS[\"bogus\",AUTHORITY[\"EPSG\",\"0\"]]".to_vec(),
+        )
+        .unwrap();
+        // the second payload replaces the first
+        write_vlr_bytes_to_header(
+            &mut builder,
+            "LASF_Projection",
+            2112,
+            crs.encode_as_wkt2_vlr_bytes(),
+        )
+        .unwrap();
+        assert_eq!(builder.vlrs.len(), 1);
+        let header = builder.clone().into_header().unwrap();
+        assert!(header.has_wkt_crs());
+        assert_eq!(header.get_epsg_crs(), Ok(Some(crs)));
+
+        assert_eq!(
+            write_vlr_bytes_to_header(&mut builder, "LASF_Spec", 2112, Vec::new()),
+            Err(Error::NotCrsVlr {
+                user_id: "LASF_Spec".to_string(),
+                record_id: 2112
+            })
+        );
+        assert!(write_vlr_bytes_to_header(&mut builder, "LASF_Projection", 4, Vec::new()).is_err());
+
+        // payloads too large for a VLR are added as an EVLR, which LAS 1.2 does not support
+        let large = vec![b' '; u16::MAX as usize + 1];
+        write_vlr_bytes_to_header(&mut builder, "LASF_Projection", 2112, large.clone()).unwrap();
+        assert_eq!((builder.vlrs.len(), builder.evlrs.len()), (0, 1));
+
+        let mut builder = Builder::from((1, 2));
+        write_vlr_bytes_to_header(
+            &mut builder,
+            "LASF_Projection",
+            2112,
+            crs.encode_as_wkt2_vlr_bytes(),
+        )
+        .unwrap();
+        assert_eq!(
+            write_vlr_bytes_to_header(&mut builder, "LASF_Projection", 2112, large),
+            Err(Error::EvlrsNotSupported { major: 1, minor: 2 })
+        );
+        assert_eq!(builder.vlrs.len(), 1);
+        assert!(builder.evlrs.is_empty());
+    }
+
+    #[test]
+    fn test_validate_crs_consistency() {
+        let dual_encoded = |wkt: EpsgCRS, geotiff: EpsgCRS| {
+            let mut builder = Builder::from((1, 4));
+            builder.vlrs.push(Vlr {
+                user_id: "LASF_Projection".to_string(),
+                record_id: 2112,
+                description: "WKT".to_string(),
+                data: wkt.encode_as_wkt2_vlr_bytes(),
+            });
+            builder.vlrs.push(Vlr {
+                user_id: "LASF_Projection".to_string(),
+                record_id: 34735,
+                description: "GeoTiff".to_string(),
+                data: geotiff.encode_as_geotiff_vlr_bytes(),
+            });
+            builder.has_wkt_crs = true;
+            builder.into_header().unwrap()
+        };
+
+        let crs = EpsgCRS::new(25832, Some(5941)).unwrap();
+        assert_eq!(validate_crs_consistency(&dual_encoded(crs, crs)), Ok(crs));
+
+        let geotiff = EpsgCRS::new(32632, Some(5941)).unwrap();
+        assert_eq!(
+            validate_crs_consistency(&dual_encoded(crs, geotiff)),
+            Err(Error::CrsSourcesDisagree { wkt: crs, geotiff })
+        );
+
+        let reader = Reader::from_path("testdata/autzen.las").unwrap();
+        assert_eq!(
+            validate_crs_consistency(reader.header()),
+            Ok(EpsgCRS::new(2994, None).unwrap())
+        );
+
+        let header = Builder::from((1, 4)).into_header().unwrap();
+        assert_eq!(validate_crs_consistency(&header), Err(Error::MissingCrs));
+    }
+
+    #[test]
+    fn test_get_epsg_crs_from_reader_truncated_wkt_vlr() {
+        let bytes = std::fs::read("testdata/autzen.copc.laz").expect("Cannot read file");
+        let wkt_start = bytes
+            .windows(8)
+            .position(|w| w == b"COMPD_CS")
+            .expect("No WKT in the file");
+
+        let result = get_epsg_crs_from_reader(std::io::Cursor::new(&bytes[..wkt_start + 500]));
+        match result {
+            Err(Error::TruncatedCrsVlr { record_id, data }) => {
+                assert_eq!(record_id, 2112);
+                assert_eq!(data, &bytes[wkt_start..wkt_start + 500]);
+            }
+            other => panic!("Expected a truncated CRS VLR, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_get_epsg_crs_from_reader_vlr_count_overrun() {
+        let mut bytes = std::fs::read("testdata/autzen.las").expect("Cannot read file");
+        bytes.truncate(1994);
+        let number_of_vlrs = u32::from_le_bytes(bytes[100..104].try_into().unwrap());
+        bytes[100..104].copy_from_slice(&(number_of_vlrs + 1).to_le_bytes());
+
+        let crs = get_epsg_crs_from_reader(std::io::Cursor::new(&bytes));
+        assert_eq!(crs, Ok(Some(EpsgCRS::new(2994, None).unwrap())));
+    }
+
+    #[test]
+    fn test_get_epsg_from_wkt_crs_bytes_with_vertical_keywords() {
+        let wkt = br#"COMPD_CS["ETRS89 / UTM zone 32N + NN2000 height",PROJCS["ETRS89 / UTM zone 32N",AUTHORITY["EPSG","25832"]],HEIGHT_CS["NN2000 height",AUTHORITY["EPSG","5941"]]]"#;
+
+        let crs = get_epsg_from_wkt_crs_bytes_with_vertical_keywords(wkt, &["HEIGHT_CS"]).unwrap();
+        assert_eq!(crs.get_horizontal(), 25832);
+        assert_eq!(crs.get_vertical(), Some(5941));
+
+        // the default keywords do not split on the custom vertical node
+        let crs = get_epsg_from_wkt_crs_bytes(wkt).unwrap();
+        assert_eq!(crs.get_vertical(), None);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_get_epsg_from_zstd_compressed_wkt_crs_bytes() {
+        let wkt = br#"PROJCS["ETRS89 / UTM zone 32N",AUTHORITY["EPSG","25832"]]"#;
+        let compressed = zstd::encode_all(&wkt[..], 0).unwrap();
+
+        let crs = get_epsg_from_wkt_crs_bytes(&compressed).unwrap();
+        assert_eq!(crs.get_horizontal(), 25832);
+
+        assert_eq!(
+            get_epsg_from_wkt_crs_bytes(&compressed[..compressed.len() - 1]),
+            Err(Error::UnreadableWktCrs)
+        );
+    }
+
+    #[test]
+    fn test_get_epsg_from_geotiff_crs_with_extras() {
+        let geotiff = GeoTiffCrs {
+            entries: vec![
+                GeoTiffKeyEntry {
+                    id: 1024,
+                    data: GeoTiffData::U16(1),
+                },
+                GeoTiffKeyEntry {
+                    id: 1025,
+                    data: GeoTiffData::U16(2),
+                },
+                GeoTiffKeyEntry {
+                    id: 1026,
+                    data: GeoTiffData::String(
+                        "ETRS89 / UTM zone 32N, ellipsoidal heights".to_string(),
+                    ),
+                },
+                GeoTiffKeyEntry {
+                    id: 3072,
+                    data: GeoTiffData::U16(25832),
+                },
+            ],
+        };
+
+        let (crs, extras) = get_epsg_from_geotiff_crs_with_extras(&geotiff).unwrap();
+        assert_eq!(crs.get_horizontal(), 25832);
+        assert_eq!(extras.raster_type, Some(2));
+        assert_eq!(
+            extras.citation.as_deref(),
+            Some("ETRS89 / UTM zone 32N, ellipsoidal heights")
+        );
+    }
+
+    #[test]
+    fn test_get_geotiff_linear_unit_autzen() {
+        let reader = Reader::from_path("testdata/autzen.las").expect("Cannot open reader");
+        let geotiff = reader.header().get_geotiff_crs().unwrap().unwrap();
+        assert_eq!(get_geotiff_linear_unit(&geotiff), Some(9002));
+    }
+
+    #[test]
+    fn test_get_epsg_crs_filtered() {
         let reader = Reader::from_path("testdata/32-1-472-150-76.laz").expect("Cannot open reader");
-        let crs = reader.header().get_epsg_crs().unwrap().unwrap();
-        assert!(crs.horizontal == 25832);
-        assert!(crs.vertical == Some(5941));
+        let header = reader.header();
+
+        let allow_list = [25832, 25833];
+        let crs = header
+            .get_epsg_crs_filtered(|crs| allow_list.contains(&crs.get_horizontal()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(crs.get_horizontal(), 25832);
+
+        let allow_list = [25833, 25835];
+        assert_eq!(
+            header.get_epsg_crs_filtered(|crs| allow_list.contains(&crs.get_horizontal())),
+            Err(Error::DisallowedCrs(crs))
+        );
     }
 
     #[test]
-    fn test_get_epsg_crs_wkt_vlr_autzen_las() {
+    fn test_tokenize_wkt_nested_brackets() {
+        let tokens = tokenize_wkt(br#"VERTCRS["NN2000",ID["EPSG",5941]]"#).unwrap();
+        let kinds: Vec<_> = tokens.iter().map(|token| token.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                WktTokenKind::Keyword("VERTCRS".to_string()),
+                WktTokenKind::OpenBracket,
+                WktTokenKind::String("NN2000".to_string()),
+                WktTokenKind::Keyword("ID".to_string()),
+                WktTokenKind::OpenBracket,
+                WktTokenKind::String("EPSG".to_string()),
+                WktTokenKind::Number("5941".to_string()),
+                WktTokenKind::CloseBracket,
+                WktTokenKind::CloseBracket,
+            ]
+        );
+        assert_eq!(tokens[3].span, 17..19);
+        assert_eq!(tokens[6].span, 27..31);
+    }
+
+    #[test]
+    fn test_tokenize_wkt_quoted_strings() {
+        let wkt = br#"PROJCS["Say ""hi"", [world]", UNIT["metre",1.0E0]]"#;
+        let tokens = tokenize_wkt(wkt).unwrap();
+        assert_eq!(
+            tokens[2].kind,
+            WktTokenKind::String(r#"Say "hi", [world]"#.to_string())
+        );
+        assert_eq!(&wkt[tokens[2].span.clone()], br#""Say ""hi"", [world]""#);
+        assert_eq!(tokens[6].kind, WktTokenKind::Number("1.0E0".to_string()));
+        assert_eq!(tokens.len(), 9);
+
+        assert_eq!(
+            tokenize_wkt(br#"PROJCS["unterminated"#),
+            Err(Error::UnreadableWktCrs)
+        );
+
+        let tokens = tokenize_wkt(b"TIMEORIGIN[1980-01-06T00:00:00.0Z]").unwrap();
+        assert_eq!(
+            tokens[2].kind,
+            WktTokenKind::DateTime("1980-01-06T00:00:00.0Z".to_string())
+        );
+        assert_eq!(tokens.len(), 4);
+    }
+
+    #[test]
+    fn test_try_from_str_with_source() {
+        let cases = [
+            ("4326", CrsStringFormat::Bare),
+            ("4326+5773", CrsStringFormat::Bare),
+            ("epsg:4326", CrsStringFormat::EpsgPrefix),
+            ("EPSG::4326", CrsStringFormat::EpsgDoubleColon),
+            ("urn:ogc:def:crs:EPSG::4326", CrsStringFormat::OgcUrn),
+        ];
+        for (s, format) in cases {
+            let (crs, parsed_format) = EpsgCRS::try_from_str_with_source(s).unwrap();
+            assert_eq!(crs.get_horizontal(), 4326);
+            assert_eq!(parsed_format, format, "{s}");
+        }
+    }
+
+    #[test]
+    fn test_utm_zone() {
+        let crs = EpsgCRS::new(25832, Some(5941)).unwrap();
+        assert_eq!(crs.utm_zone(), Some((32, Hemisphere::North)));
+
+        let crs = EpsgCRS::new(32733, None).unwrap();
+        assert_eq!(crs.utm_zone(), Some((33, Hemisphere::South)));
+
+        let crs = EpsgCRS::new(4326, None).unwrap();
+        assert_eq!(crs.utm_zone(), None);
+    }
+
+    #[cfg(feature = "validate")]
+    #[test]
+    fn test_checked_crs() {
+        let mut checked = CheckedCRS::try_from(EpsgCRS::new(25832, None).unwrap()).unwrap();
+        assert_eq!(checked.get_horizontal(), 25832);
+
+        assert_eq!(
+            checked.set_horizontal(1024),
+            Err(Error::UnregisteredCode(1024))
+        );
+        assert_eq!(checked.get_horizontal(), 25832);
+
+        assert_eq!(
+            CheckedCRS::try_from(EpsgCRS::new(1024, None).unwrap()),
+            Err(Error::UnregisteredCode(1024))
+        );
+        assert_eq!(EpsgCRS::from(checked), EpsgCRS::new(25832, None).unwrap());
+
+        // the registry only holds horizontal CRS's, compound CRS's are accepted
+        let compound = EpsgCRS::new(25832, Some(5941)).unwrap();
+        let mut checked = CheckedCRS::try_from(compound).unwrap();
+        assert_eq!(checked.get_vertical(), Some(5941));
+        assert_eq!(checked.set_vertical(5703), Ok(()));
+        assert_eq!(checked.set_vertical(0), Err(Error::SetBadCode(0)));
+        assert_eq!(checked.get_vertical(), Some(5703));
+    }
+
+    #[test]
+    fn test_get_epsg_from_wkt2_2019_ensemble_datum() {
+        let wkt = br#"GEOGCRS["WGS 84",ENSEMBLE["World Geodetic System 1984 ensemble",MEMBER["World Geodetic System 1984 (Transit)",ID["EPSG",1166]],MEMBER["World Geodetic System 1984 (G2139)",ID["EPSG",1309]],ELLIPSOID["WGS 84",6378137,298.257223563,LENGTHUNIT["metre",1],ID["EPSG",7030]],ENSEMBLEACCURACY[2.0],ID["EPSG",6326]],PRIMEM["Greenwich",0,ANGLEUNIT["degree",0.0174532925199433],ID["EPSG",8901]],CS[ellipsoidal,2,ID["EPSG",6422]],AXIS["geodetic latitude (Lat)",north,ORDER[1]],AXIS["geodetic longitude (Lon)",east,ORDER[2]],ANGLEUNIT["degree",0.0174532925199433,ID["EPSG",9122]],USAGE[SCOPE["Horizontal component of 3D system."],AREA["World."],BBOX[-90,-180,90,180]],ID["EPSG",4326]]"#;
+
+        let crs = get_epsg_from_wkt_crs_bytes(wkt).unwrap();
+        assert_eq!(crs.get_horizontal(), 4326);
+        assert_eq!(crs.get_vertical(), None);
+
+        let nodes = wkt_authority_nodes(wkt);
+        assert!(nodes.contains(&("ENSEMBLE".to_string(), 6326)));
+        assert_eq!(nodes.last(), Some(&("GEOGCRS".to_string(), 4326)));
+    }
+
+    #[test]
+    fn test_try_epsg_crs() {
+        let reader = Reader::from_path("testdata/autzen.copc.laz").expect("Cannot open reader");
+        assert_eq!(
+            reader.header().try_epsg_crs(),
+            Some(EpsgCRS::new(2992, Some(6360)).unwrap())
+        );
+
+        let header = Builder::from((1, 4)).into_header().unwrap();
+        assert_eq!(header.try_epsg_crs(), None);
+
+        let mut builder = Builder::from((1, 4));
+        builder.vlrs.push(Vlr {
+            user_id: "LASF_Projection".to_string(),
+            record_id: 2112,
+            description: "WKT".to_string(),
+            data: br#"PROJCS["unknown",AUTHORITY["EPSG","0"]]"#.to_vec(),
+        });
+        builder.has_wkt_crs = true;
+        let header = builder.into_header().unwrap();
+        assert!(matches!(
+            header.get_epsg_crs(),
+            Err(Error::BadHorizontalCodeParsed(_))
+        ));
+        assert_eq!(header.try_epsg_crs(), None);
+    }
+
+    #[test]
+    fn test_bitor_combines_horizontal_and_vertical() {
+        let horizontal = EpsgCRS::new(25832, None).unwrap();
+        let compound = EpsgCRS::new(25833, Some(5941)).unwrap();
+
+        assert_eq!(
+            horizontal | compound,
+            Ok(EpsgCRS::new(25832, Some(5941)).unwrap())
+        );
+        assert_eq!(
+            horizontal | 5941,
+            Ok(EpsgCRS::new(25832, Some(5941)).unwrap())
+        );
+        assert_eq!(compound | horizontal, Err(Error::MissingVerticalCrs));
+        assert_eq!(horizontal | 0, Err(Error::SetBadCode(0)));
+    }
+
+    #[test]
+    fn test_get_epsg_from_geotiff_crs_strict_model_mismatch() {
+        let geotiff = GeoTiffCrs {
+            entries: vec![
+                GeoTiffKeyEntry {
+                    id: 1024,
+                    data: GeoTiffData::U16(1),
+                },
+                GeoTiffKeyEntry {
+                    id: 2048,
+                    data: GeoTiffData::U16(25832),
+                },
+            ],
+        };
+
+        // the lenient default reads the code anyway
+        assert_eq!(
+            get_epsg_from_geotiff_crs(&geotiff)
+                .unwrap()
+                .get_horizontal(),
+            25832
+        );
+        assert_eq!(
+            get_epsg_from_geotiff_crs_strict(&geotiff),
+            Err(Error::GeoTiffKeyModelMismatch {
+                model_type: 1,
+                key: 2048
+            })
+        );
+    }
+
+    #[cfg(feature = "validate")]
+    #[test]
+    fn test_vertically_equivalent() {
+        let aliases = [(5941, 5776)];
+        let a = EpsgCRS::new(25832, Some(5941)).unwrap();
+        let b = EpsgCRS::new(25832, Some(5776)).unwrap();
+
+        assert!(a.vertically_equivalent(&b, &aliases));
+        assert!(b.vertically_equivalent(&a, &aliases));
+        assert!(!a.vertically_equivalent(&b, &[]));
+        assert!(!a.vertically_equivalent(&EpsgCRS::new(25832, None).unwrap(), &aliases));
+        assert!(!a.vertically_equivalent(&EpsgCRS::new(25833, Some(5776)).unwrap(), &aliases));
+    }
+
+    #[test]
+    fn test_encode_as_geotiff_vlr_bytes_round_trip() {
+        for crs in [
+            EpsgCRS::new(25832, Some(5941)).unwrap(),
+            EpsgCRS::new(4326, None).unwrap(),
+        ] {
+            let mut builder = Builder::from((1, 2));
+            builder.vlrs.push(Vlr {
+                user_id: "LASF_Projection".to_string(),
+                record_id: 34735,
+                description: "GeoTiff GeoKeyDirectoryTag".to_string(),
+                data: crs.encode_as_geotiff_vlr_bytes(),
+            });
+            let header = builder.into_header().unwrap();
+
+            let geotiff = header.get_geotiff_crs().unwrap().unwrap();
+            assert_eq!(get_epsg_from_geotiff_crs(&geotiff), Ok(crs));
+        }
+    }
+
+    #[test]
+    fn test_header_summary_autzen() {
         let reader = Reader::from_path("testdata/autzen.las").expect("Cannot open reader");
-        let crs = reader
-            .header()
-            .get_epsg_crs()
-            .expect("Could not get epsg code")
-            .expect("The found EPSG was None");
+        let header = reader.header();
+        let summary = header_summary(header);
 
-        assert!(crs.horizontal == 2994);
-        assert!(crs.vertical.is_none())
+        assert_eq!(summary.crs, Ok(Some(EpsgCRS::new(2994, None).unwrap())));
+        assert_eq!(summary.point_count, header.number_of_points());
+        assert!(summary.point_count > 0);
+        assert_eq!(summary.bounds, header.bounds());
+    }
+
+    #[test]
+    fn test_encode_as_wkt2_vlr_bytes_round_trip() {
+        for crs in [
+            EpsgCRS::new(25832, Some(5941)).unwrap(),
+            EpsgCRS::new(4326, None).unwrap(),
+        ] {
+            let wkt = crs.encode_as_wkt2_vlr_bytes();
+            assert_eq!(detect_wkt_version(&wkt), Some(WktVersion::Wkt2));
+            assert_eq!(get_epsg_from_wkt_crs_bytes(&wkt), Ok(crs));
+        }
+    }
+
+    #[test]
+    fn test_get_epsg_from_wkt_vert_cs_not_vert_datum() {
+        let wkt = br#"COMPD_CS["NAD83 / Oregon GIC Lambert (ft) + NAVD88 height (ftUS)",PROJCS["NAD83 / Oregon GIC Lambert (ft)",AUTHORITY["EPSG","2992"]],VERT_CS["NAVD88 height (ftUS)",VERT_DATUM["North American Vertical Datum 1988",2005,AUTHORITY["EPSG","5103"]],UNIT["US survey foot",0.304800609601219,AUTHORITY["EPSG","9003"]],AXIS["Gravity-related height",UP],AUTHORITY["EPSG","6360"]]]"#;
+
+        let crs = get_epsg_from_wkt_crs_bytes(wkt).unwrap();
+        assert_eq!(crs.get_vertical(), Some(6360));
+
+        // a partial keyword does not match the datum node
+        let crs = get_epsg_from_wkt_crs_bytes_with_vertical_keywords(wkt, &["VERT"]).unwrap();
+        assert_eq!(crs.get_vertical(), None);
+    }
+
+    #[test]
+    fn test_repair_copc_zero_crs() {
+        let mut builder = Builder::from((1, 4));
+        builder.vlrs.push(Vlr {
+            user_id: "LASF_Projection".to_string(),
+            record_id: 2112,
+            description: "WKT".to_string(),
+            data: br#"COMPD_CS["unknown",AUTHORITY["EPSG","0"]]"#.to_vec(),
+        });
+        builder.has_wkt_crs = true;
+        let mut header = builder.into_header().unwrap();
+
+        assert!(repair_copc_zero_crs(&mut header));
+        assert!(!header.has_wkt_crs());
+        assert_eq!(header.get_epsg_crs(), Ok(None));
+
+        // a second repair has nothing to do
+        assert!(!repair_copc_zero_crs(&mut header));
+
+        let reader = Reader::from_path("testdata/autzen.copc.laz").expect("Cannot open reader");
+        let mut header = reader.header().clone();
+        assert!(!repair_copc_zero_crs(&mut header));
+    }
+
+    #[test]
+    fn test_get_epsg_from_geokey_directory_norway() {
+        #[rustfmt::skip]
+        let keys = [
+            1, 1, 0, 4,
+            1024, 0, 1, 1,
+            1026, 34737, 22, 0,
+            3072, 0, 1, 25832,
+            4096, 0, 1, 5941,
+        ];
+        let ascii = b"ETRS89 / UTM zone 32N|\0";
+
+        let crs = get_epsg_from_geokey_directory(&keys, &[], ascii).unwrap();
+        assert_eq!(crs, EpsgCRS::new(25832, Some(5941)).unwrap());
+
+        assert_eq!(
+            get_epsg_from_geokey_directory(&keys[..10], &[], ascii),
+            Err(Error::LasError(las::Error::UnreadableGeoTiffCrs))
+        );
+    }
+
+    #[test]
+    fn test_parse_all() {
+        let reader = Reader::from_path("testdata/autzen.copc.laz").expect("Cannot open reader");
+        assert_eq!(
+            parse_all(reader.header()),
+            vec![(
+                EpsgCRS::new(2992, Some(6360)).unwrap(),
+                CrsSource::Wkt {
+                    user_id: "LASF_Projection".to_string(),
+                    record_id: 2112
+                },
+                1.0
+            )]
+        );
+
+        let mut builder = Builder::from((1, 4));
+        builder.vlrs.push(Vlr {
+            user_id: "LASF_Projection".to_string(),
+            record_id: 2112,
+            description: "WKT".to_string(),
+            data: br#"PROJCS["EPSG 25833"]"#.to_vec(),
+        });
+        builder.vlrs.push(Vlr {
+            user_id: "LASF_Projection".to_string(),
+            record_id: 34735,
+            description: "GeoTiff GeoKeyDirectoryTag".to_string(),
+            data: EpsgCRS::new(25832, None)
+                .unwrap()
+                .encode_as_geotiff_vlr_bytes(),
+        });
+        builder.has_wkt_crs = true;
+        let header = builder.into_header().unwrap();
+
+        let results = parse_all(&header);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0.get_horizontal(), 25832);
+        assert_eq!(
+            results[0].1,
+            CrsSource::GeoTiff {
+                user_id: "LASF_Projection".to_string(),
+                record_id: 34735
+            }
+        );
+        assert_eq!(results[1].0.get_horizontal(), 25833);
+        assert!(matches!(
+            results[1].1,
+            CrsSource::Wkt {
+                record_id: 2112,
+                ..
+            }
+        ));
+        assert!(results[1].2 < 1.0);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn test_encode_parse_round_trip(
+            horizontal in EPSG_RANGE,
+            vertical in proptest::option::of(EPSG_RANGE),
+        ) {
+            let crs = EpsgCRS::new(horizontal, vertical).unwrap();
+
+            let wkt = crs.encode_as_wkt2_vlr_bytes();
+            proptest::prop_assert_eq!(get_epsg_from_wkt_crs_bytes(&wkt), Ok(crs));
+
+            let mut builder = Builder::from((1, 2));
+            builder.vlrs.push(Vlr {
+                user_id: "LASF_Projection".to_string(),
+                record_id: 34735,
+                description: "GeoTiff GeoKeyDirectoryTag".to_string(),
+                data: crs.encode_as_geotiff_vlr_bytes(),
+            });
+            let geotiff = builder.into_header().unwrap().get_geotiff_crs().unwrap().unwrap();
+            proptest::prop_assert_eq!(get_epsg_from_geotiff_crs(&geotiff), Ok(crs));
+        }
+    }
+
+    #[test]
+    fn test_get_epsg_from_geotiff_crs_ascii_referenced_code_key() {
+        #[rustfmt::skip]
+        let mut keys = vec![
+            1, 1, 0, 2,
+            1024, 0, 1, 1,
+            3072, 34737, 6, 0,
+        ];
+        let crs = get_epsg_from_geokey_directory(&keys, &[], b"25832|").unwrap();
+        assert_eq!(crs.get_horizontal(), 25832);
+
+        keys[10] = 11;
+        let crs = get_epsg_from_geokey_directory(&keys, &[], b"EPSG:25833|").unwrap();
+        assert_eq!(crs.get_horizontal(), 25833);
+
+        keys[10] = 7;
+        assert!(matches!(
+            get_epsg_from_geokey_directory(&keys, &[], b"custom|"),
+            Err(Error::UnimplementedForGeoTiffStringAndDoubleData(
+                GeoTiffData::String(_)
+            ))
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_get_epsg_crs_pdal_json_metadata() {
+        let value: serde_json::Value = serde_json::from_str(
+            r#"{"metadata":{"srs":{"wkt":"PROJCS[\"ETRS89 / UTM zone 32N\",AUTHORITY[\"EPSG\",\"25832\"]]"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            value.get_epsg_crs(),
+            Ok(Some(EpsgCRS::new(25832, None).unwrap()))
+        );
+
+        let value: serde_json::Value =
+            serde_json::from_str(r#"{"metadata":{"srs":{"wkt":"","epsg":"EPSG:25833"}}}"#).unwrap();
+        assert_eq!(
+            value.get_epsg_crs(),
+            Ok(Some(EpsgCRS::new(25833, None).unwrap()))
+        );
+
+        let value: serde_json::Value = serde_json::from_str(r#"{"metadata":{}}"#).unwrap();
+        assert_eq!(value.get_epsg_crs(), Ok(None));
+    }
+
+    #[test]
+    fn test_area_of_use_wgs84() {
+        let [west, south, east, north] = EpsgCRS::new(25832, None)
+            .unwrap()
+            .area_of_use_wgs84()
+            .unwrap();
+        assert_eq!((west, east), (6.0, 12.0));
+        assert!((30.0..40.0).contains(&south) && (80.0..=90.0).contains(&north));
+
+        let [west, south, east, north] = EpsgCRS::new(32733, None)
+            .unwrap()
+            .area_of_use_wgs84()
+            .unwrap();
+        assert_eq!([west, south, east, north], [12.0, -80.0, 18.0, 0.0]);
+
+        assert_eq!(EpsgCRS::new(2992, None).unwrap().area_of_use_wgs84(), None);
+    }
+
+    #[test]
+    fn test_vertical_category() {
+        let category = |vertical| EpsgCRS::new(25832, vertical).unwrap().vertical_category();
+        assert_eq!(category(None), None);
+        assert_eq!(category(Some(5703)), Some(VerticalCategory::MeanSeaLevel));
+        assert_eq!(category(Some(3855)), Some(VerticalCategory::Geoid));
+        assert_eq!(category(Some(5941)), Some(VerticalCategory::Unknown));
+    }
+
+    #[test]
+    fn test_get_epsg_from_wkt2_2019_usage_bbox() {
+        let wkt = br#"PROJCRS["ETRS89 / UTM zone 32N",BASEGEOGCRS["ETRS89",DATUM["European Terrestrial Reference System 1989",ELLIPSOID["GRS 1980",6378137,298.257222101]],ID["EPSG",4258]],CONVERSION["UTM zone 32N",METHOD["Transverse Mercator",ID["EPSG",9807]]],CS[Cartesian,2],AXIS["(E)",east],AXIS["(N)",north],LENGTHUNIT["metre",1],ID["EPSG",25832],USAGE[SCOPE["Engineering survey, topographic mapping."],AREA["Europe between 6 and 12 degrees east."],BBOX[38.76,6,84.33,12]]]"#;
+
+        let (crs, diagnostics) = get_epsg_from_wkt_crs_bytes_with_diagnostics(wkt);
+        assert_eq!(crs, Ok(EpsgCRS::new(25832, None).unwrap()));
+        assert!(!diagnostics.used_fallback_heuristic);
+    }
+
+    #[test]
+    fn test_error_user_defined_crs() {
+        let geotiff = GeoTiffCrs {
+            entries: vec![GeoTiffKeyEntry {
+                id: 1024,
+                data: GeoTiffData::U16(32767),
+            }],
+        };
+        assert_eq!(
+            get_epsg_from_geotiff_crs(&geotiff),
+            Err(Error::UserDefinedCrs)
+        );
+    }
+
+    #[test]
+    fn test_error_unreadable_wkt_crs() {
+        assert_eq!(
+            get_epsg_from_wkt_crs_bytes(b"\xff\x00garbage 25832"),
+            Err(Error::UnreadableWktCrs)
+        );
+        assert_eq!(
+            get_epsg_from_wkt_crs_bytes(b""),
+            Err(Error::UnreadableWktCrs)
+        );
+    }
+
+    #[test]
+    fn test_error_bad_horizontal_code_parsed() {
+        assert_eq!(
+            get_epsg_from_wkt_crs_bytes(br#"PROJCS["unknown",AUTHORITY["EPSG","1"]]"#),
+            Err(Error::BadHorizontalCodeParsed(EpsgCRS::new_unchecked(
+                1, None
+            )))
+        );
+    }
+
+    #[test]
+    fn test_error_unimplemented_for_geotiff_string_and_double_data() {
+        let geotiff = GeoTiffCrs {
+            entries: vec![GeoTiffKeyEntry {
+                id: 1024,
+                data: GeoTiffData::Doubles(vec![1.0]),
+            }],
+        };
+        assert_eq!(
+            get_epsg_from_geotiff_crs(&geotiff),
+            Err(Error::UnimplementedForGeoTiffStringAndDoubleData(
+                GeoTiffData::Doubles(vec![1.0])
+            ))
+        );
+    }
+
+    #[test]
+    fn test_error_set_bad_code() {
+        let mut crs = EpsgCRS::new(25832, None).unwrap();
+        assert_eq!(crs.set_horizontal(1), Err(Error::SetBadCode(1)));
+        assert_eq!(crs.set_vertical(40000), Err(Error::SetBadCode(40000)));
+        assert_eq!(crs, EpsgCRS::new(25832, None).unwrap());
+    }
+
+    #[test]
+    fn test_replace_vertical() {
+        let mut crs = EpsgCRS::new(25832, None).unwrap();
+        assert_eq!(crs.replace_vertical(5941), Err(Error::NoVerticalToReplace));
+        assert_eq!(crs.get_vertical(), None);
+
+        let mut crs = EpsgCRS::new(25832, Some(5776)).unwrap();
+        assert_eq!(crs.replace_vertical(5941), Ok(()));
+        assert_eq!(crs.get_vertical(), Some(5941));
+    }
+
+    #[test]
+    fn test_get_epsg_crs_from_path_fixtures() {
+        let fixtures = [
+            ("testdata/autzen.las", EpsgCRS::new(2994, None)),
+            ("testdata/autzen.copc.laz", EpsgCRS::new(2992, Some(6360))),
+            (
+                "testdata/32-1-472-150-76.laz",
+                EpsgCRS::new(25832, Some(5941)),
+            ),
+        ];
+        for (path, crs) in fixtures {
+            assert_eq!(
+                get_epsg_crs_from_path(path),
+                Ok(Some(crs.unwrap())),
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_epsg_from_gdal_aux_xml() {
+        let xml = br#"<PAMDataset>
+  <SRS dataAxisToSRSAxisMapping="1,2">PROJCS[&quot;ETRS89 / UTM zone 32N&quot;,GEOGCS[&quot;ETRS89&quot;,AUTHORITY[&quot;EPSG&quot;,&quot;4258&quot;]],AUTHORITY[&quot;EPSG&quot;,&quot;25832&quot;]]</SRS>
+</PAMDataset>"#;
+        assert_eq!(
+            get_epsg_from_gdal_aux_xml(xml),
+            Ok(Some(EpsgCRS::new(25832, None).unwrap()))
+        );
+        assert_eq!(
+            get_epsg_from_gdal_aux_xml(b"<PAMDataset></PAMDataset>"),
+            Ok(None)
+        );
+
+        // tags starting with SRS are not the SRS element
+        assert_eq!(
+            get_epsg_from_gdal_aux_xml(b"<PAMDataset><SRSName>EPSG:4326</SRSName></PAMDataset>"),
+            Ok(None)
+        );
+        let xml = br#"<PAMDataset><SRSName>WGS 84</SRSName><SRS>GEOGCS["WGS 84",AUTHORITY["EPSG","4326"]]</SRS></PAMDataset>"#;
+        assert_eq!(
+            get_epsg_from_gdal_aux_xml(xml),
+            Ok(Some(EpsgCRS::new(4326, None).unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_get_epsg_from_wkt2_geodcrs() {
+        let wkt = br#"GEODCRS["WGS 84",DATUM["World Geodetic System 1984",ELLIPSOID["WGS 84",6378137,298.257223563,LENGTHUNIT["metre",1]]],PRIMEM["Greenwich",0,ANGLEUNIT["degree",0.0174532925199433]],CS[Cartesian,3],AXIS["(X)",geocentricX],AXIS["(Y)",geocentricY],AXIS["(Z)",geocentricZ],LENGTHUNIT["metre",1],ID["EPSG",4978],USAGE[SCOPE["Geodesy."],AREA["World."],BBOX[-90,-180,90,180]]]"#;
+        assert_eq!(detect_wkt_version(wkt), Some(WktVersion::Wkt2));
+        assert_eq!(
+            get_epsg_from_wkt_crs_bytes(wkt),
+            Ok(EpsgCRS::new(4978, None).unwrap())
+        );
+
+        let wkt = br#"COMPOUNDCRS["WGS 84 + EGM2008 height",GEODETICCRS["WGS 84",ID["EPSG",4326]],VERTCRS["EGM2008 height",ID["EPSG",3855]]]"#;
+        assert_eq!(detect_wkt_version(wkt), Some(WktVersion::Wkt2));
+        assert_eq!(
+            get_epsg_from_wkt_crs_bytes(wkt),
+            Ok(EpsgCRS::new(4326, Some(3855)).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_intersection() {
+        let a = EpsgCRS::new(25832, Some(5941)).unwrap();
+        let b = EpsgCRS::new(25832, Some(5776)).unwrap();
+        let c = EpsgCRS::new(25833, Some(5941)).unwrap();
+
+        assert_eq!(EpsgCRS::intersection(&a, &a), Some(a));
+        assert_eq!(a.intersection(&b), Some(EpsgCRS::new(25832, None).unwrap()));
+        assert_eq!(a.intersection(&c), None);
+    }
+
+    #[cfg(feature = "validate")]
+    #[test]
+    fn test_require_projected() {
+        assert_eq!(
+            EpsgCRS::new(25832, None).unwrap().require_projected(),
+            Ok(())
+        );
+        assert_eq!(
+            EpsgCRS::new(4326, None).unwrap().require_projected(),
+            Err(Error::ExpectedProjected(4326))
+        );
     }
 }